@@ -0,0 +1,65 @@
+use std::{error::Error, fmt};
+
+use infograph::types::Point;
+
+///
+/// The errors returned by the category services.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CatError {
+
+    ///
+    /// A point that should have a descriptor note does not have one. Holds the point.
+    ///
+    MissingDescriptor(Point),
+
+    ///
+    /// An info triple does not have the shape the category logic expects. Holds a description of
+    /// the problem.
+    ///
+    MalformedTriple(String),
+
+    ///
+    /// A category was referenced that does not exist in the space. Holds the id used.
+    ///
+    UnknownCat(String),
+
+    ///
+    /// A relation between categories would make a category its own ancestor or condition. Holds
+    /// the IDs of the categories involved.
+    ///
+    Cycle(Vec<String>),
+
+    ///
+    /// A condition expression cannot be stored, for example because it has an empty group. Holds a
+    /// description of the problem.
+    ///
+    InvalidExpression(String),
+
+    ///
+    /// A property or value cannot be stored or read back, for example because its key is empty.
+    /// Holds a description of the problem.
+    ///
+    InvalidValue(String),
+
+    ///
+    /// The underlying storage failed to read or write. Holds a description of the problem.
+    ///
+    Storage(String),
+}
+
+impl fmt::Display for CatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CatError::MissingDescriptor(point) => write!(f, "No descriptor note found for point {}", point),
+            CatError::MalformedTriple(reason) => write!(f, "Malformed info triple: {}", reason),
+            CatError::UnknownCat(cat_id) => write!(f, "Unknown category {}", cat_id),
+            CatError::Cycle(cat_ids) => write!(f, "Categories would form a cycle: {}", cat_ids.join(" -> ")),
+            CatError::InvalidExpression(reason) => write!(f, "Invalid condition expression: {}", reason),
+            CatError::InvalidValue(reason) => write!(f, "Invalid value: {}", reason),
+            CatError::Storage(reason) => write!(f, "Storage error: {}", reason),
+        }
+    }
+}
+
+impl Error for CatError {}
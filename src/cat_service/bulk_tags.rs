@@ -0,0 +1,211 @@
+use std::collections::{HashMap, HashSet};
+
+use infograph::types::Point;
+use infograph::InfoTriple;
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, MAIN_TABLE, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_element::CatElement;
+use crate::model::cat_list::CatList;
+use crate::model::cat_stamp::CatStamp;
+use crate::model::cat_tag_outcome::CatTagOutcome;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Tags each point in pairs with the cat it is paired with, like create_cat_element, and returns
+    /// an outcome for each pair in the same order.
+    /// The space is read once and the writes share one stamp, which makes this the way to tag many
+    /// points at once. Pairs already tagged are skipped and a pair failing its checks, for example
+    /// because its cat is not a cat currently in the space, does not stop the others. Only a failing
+    /// storage fails the call as a whole.
+    ///
+    pub fn tag_many(&mut self, pairs: Vec<(Cat, Point)>) -> Result<Vec<CatTagOutcome>, CatError> {
+
+        let (info_triples, cats) = self.load_live(MAIN_TABLE.to_string())?;
+        self.write_tags(&info_triples, &cats, pairs, &mut None)
+    }
+
+    ///
+    /// Does the work of tag_many with the triples currently in effect and the cats loaded from
+    /// them given as info_triples and cats. The writes are stamped with stamp, which is created on
+    /// the first write if None, so callers writing more can share it.
+    ///
+    pub(super) fn write_tags(&mut self, info_triples: &[InfoTriple], cats: &CatList, pairs: Vec<(Cat, Point)>, stamp: &mut Option<(CatStamp, String)>) -> Result<Vec<CatTagOutcome>, CatError> {
+
+        let mut tags = self.get_tags(info_triples, cats);
+        let descs = self.store.get_descs_hashmap_for_list(pairs.iter().map(|(_, point)|point.clone()).collect())?;
+        let mut siblings: HashMap<String, HashSet<Point>> = HashMap::new();
+
+        let mut result: Vec<CatTagOutcome> = Vec::new();
+        for (cat, point) in pairs {
+            let cat = match check_tag(cats, cat, &point) {
+                Ok(cat) => cat,
+                Err(error) => {
+                    result.push(CatTagOutcome::Failed(error));
+                    continue;
+                },
+            };
+            if tags.contains_key(&(cat.point.clone(), point.clone())) {
+                result.push(CatTagOutcome::Skipped);
+                continue;
+            }
+
+            let (cat_stamp, stamp_point) = match stamp.clone() {
+                Some(stamp) => stamp,
+                None => stamp.insert(self.create_stamp()?).clone(),
+            };
+            let it = self.store.create_infotriple(cat.point.clone(), point.clone())?;
+            self.write_stamp(it.id.clone(), stamp_point.clone())?;
+
+            let sibling_points = siblings
+                .entry(cat.triple_id.clone())
+                .or_insert_with(|| self.get_facet_sibling_points(info_triples, &cat));
+            for sibling_point in sibling_points.iter() {
+                if let Some(sibling_tag) = tags.remove(&(sibling_point.clone(), point.clone())) {
+                    let canceled = self.store.create_infotriple(sibling_tag.id, self.canceled_tag.clone())?;
+                    self.write_stamp(canceled.id, stamp_point.clone())?;
+                }
+            }
+            tags.insert((cat.point.clone(), point.clone()), it.clone());
+
+            let mut cat_element = CatElement::from_triple(cat.point, it)?;
+            cat_element.stamp = Some(cat_stamp);
+            if let Some(desc) = descs.get(&point) {
+                cat_element.add_desc(desc.clone());
+            }
+            result.push(CatTagOutcome::Written(cat_element));
+        }
+
+        Ok(result)
+    }
+
+    ///
+    /// Removes the tag of each point in pairs with the cat it is paired with, like
+    /// remove_cat_element, and returns an outcome for each pair in the same order.
+    /// Like tag_many the space is read once and the writes share one stamp. Pairs not tagged are
+    /// skipped and a pair failing its checks does not stop the others.
+    ///
+    pub fn untag_many(&mut self, pairs: Vec<(Cat, Point)>) -> Result<Vec<CatTagOutcome>, CatError> {
+
+        let (info_triples, cats) = self.load_live(MAIN_TABLE.to_string())?;
+        let mut tags = self.get_tags(&info_triples, &cats);
+        let descs = self.store.get_descs_hashmap_for_list(pairs.iter().map(|(_, point)|point.clone()).collect())?;
+        let mut stamp_point: Option<String> = None;
+
+        let mut result: Vec<CatTagOutcome> = Vec::new();
+        for (cat, point) in pairs {
+            let cat = match check_tag(&cats, cat, &point) {
+                Ok(cat) => cat,
+                Err(error) => {
+                    result.push(CatTagOutcome::Failed(error));
+                    continue;
+                },
+            };
+            let it = match tags.remove(&(cat.point.clone(), point.clone())) {
+                Some(it) => it,
+                None => {
+                    result.push(CatTagOutcome::Skipped);
+                    continue;
+                },
+            };
+
+            let stamp_point = match stamp_point.clone() {
+                Some(stamp_point) => stamp_point,
+                None => stamp_point.insert(self.create_stamp()?.1).clone(),
+            };
+            let canceled = self.store.create_infotriple(it.id.clone(), self.canceled_tag.clone())?;
+            self.write_stamp(canceled.id, stamp_point)?;
+
+            let mut cat_element = CatElement::from_triple(cat.point, it)?;
+            if let Some(desc) = descs.get(&point) {
+                cat_element.add_desc(desc.clone());
+            }
+            result.push(CatTagOutcome::Written(cat_element));
+        }
+
+        Ok(result)
+    }
+
+    ///
+    /// Returns the triples tagging points with the cats in cats found in info_triples, keyed by the
+    /// cat's point and the tagged point.
+    ///
+    fn get_tags(&self, info_triples: &[InfoTriple], cats: &CatList) -> HashMap<(Point, Point), InfoTriple> {
+
+        let cat_points: HashSet<Point> = cats.clone().into_iter().map(|cat|cat.point).collect();
+        let mut result: HashMap<(Point, Point), InfoTriple> = HashMap::new();
+        info_triples
+            .iter()
+            .filter(|it|it.id1 != self.cat_tag && it.id2 != self.cat_tag)
+            .for_each(|it|{
+                [(&it.id1, &it.id2), (&it.id2, &it.id1)]
+                    .iter()
+                    .filter(|(cat_point, _)|cat_points.contains(*cat_point))
+                    .for_each(|(cat_point, point)|{
+                        result.insert((cat_point.to_string(), point.to_string()), it.clone());
+                    });
+            });
+
+        result
+    }
+}
+
+///
+/// Checks a pair of tag_many or untag_many and returns the cat as found in cats.
+///
+fn check_tag(cats: &CatList, cat: Cat, point: &str) -> Result<Cat, CatError> {
+    check_id(point)?;
+    cats.get_by_triple_id(cat.triple_id.clone())
+        .ok_or(CatError::UnknownCat(cat.triple_id))
+}
+
+#[test]
+fn tag_many_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let todo = cat_service.create_cat("a", "Todo", "", "").unwrap();
+    let done = cat_service.create_cat("b", "Done", "", "").unwrap();
+    let state = cat_service.create_cat_facet("state", "State", "").unwrap();
+    let state = cat_service.add_cat_to_facet(state, todo.clone()).unwrap();
+    cat_service.add_cat_to_facet(state, done.clone()).unwrap();
+    cat_service.store.create_desc("note1".to_string(), "Note 1".to_string(), "".to_string(), "".to_string()).unwrap();
+    cat_service.create_cat_element(todo.clone(), "note3".to_string()).unwrap();
+
+    cat_service.set_actor("importer").unwrap();
+    let outcomes = cat_service.tag_many(vec![
+        (todo.clone(), "note1".to_string()),
+        (todo.clone(), "note2".to_string()),
+        (todo.clone(), "note1".to_string()),
+        (Cat::new(), "note1".to_string()),
+        (todo.clone(), "no good".to_string()),
+        (done.clone(), "note3".to_string()),
+    ]).unwrap();
+    assert_eq!(outcomes.len(), 6);
+    match &outcomes[0] {
+        CatTagOutcome::Written(cat_element) => {
+            assert_eq!(cat_element.name, "Note 1");
+            assert_eq!(cat_element.stamp.clone().unwrap().actor, "importer");
+        },
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+    assert_eq!(outcomes[2], CatTagOutcome::Skipped);
+    assert_eq!(outcomes[3], CatTagOutcome::Failed(CatError::UnknownCat("".to_string())));
+    assert!(matches!(outcomes[4], CatTagOutcome::Failed(CatError::MalformedTriple(..))));
+
+    assert_eq!(element_points(cat_service.get_cat_elements(graph(), "a".to_string()).unwrap()), vec!["note1", "note2"]);
+    // note3 moved from todo to done as both are in the same facet
+    assert_eq!(element_points(cat_service.get_cat_elements(graph(), "b".to_string()).unwrap()), vec!["note3"]);
+
+    let outcomes = cat_service.untag_many(vec![
+        (todo.clone(), "note1".to_string()),
+        (todo.clone(), "note1".to_string()),
+        (todo.clone(), "note3".to_string()),
+    ]).unwrap();
+    assert!(matches!(&outcomes[0], CatTagOutcome::Written(cat_element) if cat_element.point == "note1"));
+    assert_eq!(outcomes[1..], [CatTagOutcome::Skipped, CatTagOutcome::Skipped]);
+    assert_eq!(element_points(cat_service.get_cat_elements(graph(), "a".to_string()).unwrap()), vec!["note2"]);
+}
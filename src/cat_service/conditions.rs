@@ -0,0 +1,496 @@
+use std::collections::{HashMap, HashSet};
+
+use infograph::framework::ig_tools::hashing_tools;
+use infograph::InfoTriple;
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, MAIN_TABLE, check_id};
+use crate::model::cat_condition::CatCondition;
+use crate::model::cat_condition_issue::CatConditionIssue;
+use crate::model::cat_expr::CatExpr;
+use crate::model::cat_stamp::CatStamp;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Makes the cat with the triple ID sub_cat_id conditioned by the expression condition and
+    /// returns the resulting CatCondition.
+    /// Every And, Or and Not of the expression is stored as a node whose ID is derived from its
+    /// operator and operands. The node is decorated with the tag of its operator and that decoration
+    /// is paired with each operand, so storing the same expression twice reuses its nodes.
+    /// Fails with CatError::UnknownCat if sub_cat_id or a cat in condition is not a cat currently
+    /// in the space and with CatError::Cycle if a cat in condition is itself conditioned, directly
+    /// or not, on sub_cat_id.
+    ///
+    pub fn create_cat_condi_expr(&mut self, condition: CatExpr, sub_cat_id: String) -> Result<CatCondition, CatError> {
+        check_id(&sub_cat_id)?;
+        check_expr(&condition)?;
+
+        let state = self.load_condition_state(MAIN_TABLE.to_string())?;
+        let cat_ids = condition.cat_ids();
+        for cat_id in std::iter::once(&sub_cat_id).chain(cat_ids.iter()) {
+            if !state.live_cats.contains(cat_id) {
+                return Err(CatError::UnknownCat(cat_id.clone()));
+            }
+        }
+        let depends_on = state.depends_on();
+        for cat_id in cat_ids.iter() {
+            if let Some(path) = condition_path(&depends_on, cat_id, &sub_cat_id) {
+                return Err(CatError::Cycle(std::iter::once(sub_cat_id).chain(path).collect()));
+            }
+        }
+
+        let stamp = self.create_stamp()?;
+        self.write_cat_condi(condition, sub_cat_id, &stamp)
+    }
+
+    ///
+    /// Returns the conditions of the cat with the triple ID sub_cat_id currently in effect, in the
+    /// order they were created, with their stamps.
+    ///
+    pub fn get_cat_condis(&mut self, graph_name: String, sub_cat_id: String) -> Result<Vec<CatCondition>, CatError> {
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let info_triples = self.live_info_triples(info_triples);
+        self.select_cat_condis(&info_triples, &sub_cat_id)
+    }
+
+    ///
+    /// Like get_cat_condis, but for the triples currently in effect given as info_triples.
+    ///
+    pub(super) fn select_cat_condis(&self, info_triples: &[InfoTriple], sub_cat_id: &String) -> Result<Vec<CatCondition>, CatError> {
+
+        let nodes = self.get_expr_nodes(info_triples);
+
+        let conditioned_id = match info_triples
+            .iter()
+            .find(|it|it.other_half(self.conditioned_tag.clone()).is_ok_and(|cat_id|cat_id == *sub_cat_id)) {
+            Some(conditioned) => conditioned.id.clone(),
+            None => return Ok(Vec::new()),
+        };
+
+        let mut result: Vec<CatCondition> = Vec::new();
+        info_triples
+            .iter()
+            .filter_map(|it|it.other_half(conditioned_id.clone()).ok().map(|root_id|(it.id.clone(), root_id)))
+            .for_each(|(triple_id, root_id)|{
+                if !result.iter().any(|condition|condition.triple_id == triple_id) {
+                    result.push(CatCondition {
+                        triple_id,
+                        conditioned_id: conditioned_id.clone(),
+                        condition: nodes.read(&root_id),
+                        super_cat_id: root_id,
+                        sub_cat_id: sub_cat_id.clone(),
+                        stamp: None,
+                    });
+                }
+            });
+
+        let triple_ids: Vec<String> = result.iter().map(|condition|condition.triple_id.clone()).collect();
+        let mut stamps = self.get_stamps(info_triples, &triple_ids)?;
+        result.iter_mut().for_each(|condition|condition.stamp = stamps.remove(&condition.triple_id));
+        Ok(result)
+    }
+
+    ///
+    /// Removes a condition by canceling its triple and returns the removed CatCondition. The other
+    /// conditions of the cat stay in effect.
+    ///
+    pub fn remove_cat_condi(&mut self, cat_condition: &CatCondition) -> Result<CatCondition, CatError> {
+        check_id(&cat_condition.triple_id)?;
+        self.cancel_infotriple(cat_condition.triple_id.clone())?;
+        Ok(cat_condition.clone())
+    }
+
+    ///
+    /// Replaces a condition by the expression condition, for example to swap its super cat, and
+    /// returns the new CatCondition. The new condition is created before the old one is removed, so
+    /// if it is rejected the old one stays in effect.
+    ///
+    pub fn replace_cat_condi(&mut self, cat_condition: &CatCondition, condition: CatExpr) -> Result<CatCondition, CatError> {
+        let result = self.create_cat_condi_expr(condition, cat_condition.sub_cat_id.clone())?;
+        if result.triple_id != cat_condition.triple_id {
+            self.remove_cat_condi(cat_condition)?;
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Removes all conditions of the cat with the triple ID sub_cat_id, so the cat is always shown,
+    /// and returns the removed conditions.
+    ///
+    pub fn clear_cat_condis(&mut self, sub_cat_id: String) -> Result<Vec<CatCondition>, CatError> {
+        let mut result = Vec::new();
+        for cat_condition in self.get_cat_condis(MAIN_TABLE.to_string(), sub_cat_id)? {
+            result.push(self.remove_cat_condi(&cat_condition)?);
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Checks the conditions of the cats currently in graph_name and returns the problems found:
+    /// conditions referring to deleted or unknown cats and cats conditioned on each other in a
+    /// circle. Conditions of deleted cats are not checked.
+    ///
+    pub fn validate_conditions(&mut self, graph_name: String) -> Result<Vec<CatConditionIssue>, CatError> {
+
+        let state = self.load_condition_state(graph_name)?;
+        let mut sub_cat_ids: Vec<&String> = state.conditions.keys().collect();
+        sub_cat_ids.sort();
+
+        let mut result = Vec::new();
+        for sub_cat_id in sub_cat_ids.iter() {
+            let mut cat_ids = state.conditions[*sub_cat_id].cat_ids();
+            cat_ids.sort();
+            cat_ids.dedup();
+            cat_ids
+                .into_iter()
+                .filter(|cat_id|!state.live_cats.contains(cat_id))
+                .for_each(|cat_id|{
+                    let sub_cat_id = sub_cat_id.to_string();
+                    result.push(if state.created_cats.contains(&cat_id) {
+                        CatConditionIssue::CanceledCat { sub_cat_id, cat_id }
+                    } else {
+                        CatConditionIssue::UnknownCat { sub_cat_id, cat_id }
+                    });
+                });
+        }
+
+        let depends_on = state.depends_on();
+        let mut cycles: HashSet<Vec<String>> = HashSet::new();
+        for sub_cat_id in sub_cat_ids {
+            let cycle = depends_on[sub_cat_id]
+                .iter()
+                .find_map(|cat_id|condition_path(&depends_on, cat_id, sub_cat_id));
+            if let Some(cycle) = cycle {
+                let mut members = cycle.clone();
+                members.sort();
+                if cycles.insert(members) {
+                    result.push(CatConditionIssue::Cycle(std::iter::once(sub_cat_id.clone()).chain(cycle).collect()));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    ///
+    /// Loads what the checks on conditions need from graph_name without loading any descriptor notes.
+    ///
+    fn load_condition_state(&mut self, graph_name: String) -> Result<ConditionState, CatError> {
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let cat_ids = |info_triples: &[InfoTriple]| -> HashSet<String> {
+            info_triples
+                .iter()
+                .filter(|it|it.other_half(self.cat_tag.clone()).is_ok())
+                .map(|it|it.id.clone())
+                .collect()
+        };
+        let created_cats = cat_ids(&info_triples);
+        let info_triples = self.live_info_triples(info_triples);
+        let live_cats = cat_ids(&info_triples);
+        let conditions = self.get_condition_exprs(&info_triples)
+            .into_iter()
+            .filter(|(sub_cat_id, _)|live_cats.contains(sub_cat_id))
+            .collect();
+
+        Ok(ConditionState { created_cats, live_cats, conditions })
+    }
+
+    ///
+    /// Returns the condition expression of each conditioned cat in info_triples keyed by the cat's
+    /// triple ID. A cat with more than one condition gets a CatExpr::Or of its conditions.
+    ///
+    pub(super) fn get_condition_exprs(&self, info_triples: &[InfoTriple]) -> HashMap<String, CatExpr> {
+
+        let nodes = self.get_expr_nodes(info_triples);
+
+        self.get_decoration_values(info_triples, self.conditioned_tag.clone())
+            .into_iter()
+            .map(|(sub_cat_id, root_ids)|{
+                let mut conditions = nodes.read_all(&root_ids);
+                let condition = if conditions.len() == 1 { conditions.remove(0) } else { CatExpr::Or(conditions) };
+                (sub_cat_id, condition)
+            })
+            .collect()
+    }
+
+    ///
+    /// Collects the stored expression nodes found in info_triples.
+    ///
+    pub(super) fn get_expr_nodes(&self, info_triples: &[InfoTriple]) -> ExprNodes {
+        ExprNodes {
+            and: self.get_decoration_values(info_triples, self.and_tag.clone()),
+            or: self.get_decoration_values(info_triples, self.or_tag.clone()),
+            not: self.get_decoration_values(info_triples, self.not_tag.clone()),
+        }
+    }
+
+    ///
+    /// Writes condition as a condition of the cat with the triple ID sub_cat_id, stamped with stamp
+    /// as returned by create_stamp, and returns the resulting CatCondition. Nothing is checked, see
+    /// create_cat_condi_expr for the checks.
+    ///
+    pub(super) fn write_cat_condi(&mut self, condition: CatExpr, sub_cat_id: String, stamp: &(CatStamp, String)) -> Result<CatCondition, CatError> {
+        let root_id = self.write_expr(&condition)?;
+        //set sub_cat_id as conditioned
+        let conditioned = self.store.create_infotriple(sub_cat_id.clone(), self.conditioned_tag.clone())?;
+        //pair the expression with conditioned triple id
+        let result = self.store.create_infotriple(conditioned.id.clone(), root_id.clone())?;
+        self.write_stamp(result.id.clone(), stamp.1.clone())?;
+
+        Ok(CatCondition {
+            triple_id: result.id,
+            conditioned_id: conditioned.id,
+            super_cat_id: root_id,
+            sub_cat_id,
+            condition,
+            stamp: Some(stamp.0.clone()),
+        })
+    }
+
+    ///
+    /// Stores the nodes of expr, operands first, and returns the ID standing for expr. A single cat
+    /// stands for itself.
+    ///
+    fn write_expr(&mut self, expr: &CatExpr) -> Result<String, CatError> {
+
+        let (tag, operands) = match expr {
+            CatExpr::Cat(cat_id) => return Ok(cat_id.clone()),
+            CatExpr::And(exprs) => (self.and_tag.clone(), exprs.iter().collect::<Vec<_>>()),
+            CatExpr::Or(exprs) => (self.or_tag.clone(), exprs.iter().collect::<Vec<_>>()),
+            CatExpr::Not(expr) => (self.not_tag.clone(), vec![expr.as_ref()]),
+        };
+
+        let mut operand_ids = Vec::new();
+        for operand in operands {
+            operand_ids.push(self.write_expr(operand)?);
+        }
+
+        let node_id = hashing_tools::hash_text(&format!("{} {}", tag, operand_ids.join(" ")));
+        let node = self.store.create_infotriple(node_id.clone(), tag)?;
+        for operand_id in operand_ids {
+            self.store.create_infotriple(node.id.clone(), operand_id)?;
+        }
+
+        Ok(node_id)
+    }
+}
+
+///
+/// The triple IDs of all cats ever created and of the cats currently in the space, together with
+/// the conditions of the latter.
+///
+struct ConditionState {
+    created_cats: HashSet<String>,
+    live_cats: HashSet<String>,
+    conditions: HashMap<String, CatExpr>,
+}
+
+impl ConditionState {
+
+    ///
+    /// Returns for each conditioned cat the cats its condition refers to.
+    ///
+    fn depends_on(&self) -> HashMap<String, Vec<String>> {
+        self.conditions
+            .iter()
+            .map(|(sub_cat_id, condition)|(sub_cat_id.clone(), condition.cat_ids()))
+            .collect()
+    }
+}
+
+///
+/// Follows the conditions in depends_on from the cat from and returns the cats on the way to the
+/// cat to, both included, or None if to cannot be reached.
+///
+pub(super) fn condition_path(depends_on: &HashMap<String, Vec<String>>, from: &String, to: &String) -> Option<Vec<String>> {
+    let mut visited: HashSet<&String> = HashSet::new();
+    let mut todo: Vec<Vec<&String>> = vec![vec![from]];
+    while let Some(path) = todo.pop() {
+        let last = path[path.len() - 1];
+        if last == to {
+            return Some(path.into_iter().cloned().collect());
+        }
+        if visited.insert(last) {
+            depends_on
+                .get(last)
+                .into_iter()
+                .flatten()
+                .for_each(|next|{
+                    let mut path = path.clone();
+                    path.push(next);
+                    todo.push(path);
+                });
+        }
+    }
+    None
+}
+
+///
+/// The operands of the stored And, Or and Not nodes keyed by node ID.
+///
+pub(super) struct ExprNodes {
+    and: HashMap<String, Vec<String>>,
+    or: HashMap<String, Vec<String>>,
+    not: HashMap<String, Vec<String>>,
+}
+
+impl ExprNodes {
+
+    ///
+    /// Rebuilds the expression standing for id. An ID that is not a node is a cat.
+    ///
+    pub(super) fn read(&self, id: &String) -> CatExpr {
+        if let Some(operands) = self.and.get(id) {
+            CatExpr::And(self.read_all(operands))
+        } else if let Some(operands) = self.or.get(id) {
+            CatExpr::Or(self.read_all(operands))
+        } else if let Some(operands) = self.not.get(id) {
+            let mut operands = self.read_all(operands);
+            let operand = if operands.len() == 1 { operands.remove(0) } else { CatExpr::Or(operands) };
+            CatExpr::Not(Box::new(operand))
+        } else {
+            CatExpr::Cat(id.clone())
+        }
+    }
+
+    ///
+    /// Like read for a list of IDs. IDs appended more than once are read once.
+    ///
+    fn read_all(&self, ids: &[String]) -> Vec<CatExpr> {
+        let mut seen: Vec<&String> = Vec::new();
+        ids.iter()
+            .filter(|id|{
+                let first = !seen.contains(id);
+                seen.push(id);
+                first
+            })
+            .map(|id|self.read(id))
+            .collect()
+    }
+}
+
+///
+/// Checks that expr can be stored: every cat ID has to be usable in an info triple and no And or Or
+/// may be empty.
+///
+pub(super) fn check_expr(expr: &CatExpr) -> Result<(), CatError> {
+    match expr {
+        CatExpr::Cat(cat_id) => check_id(cat_id),
+        CatExpr::And(exprs) | CatExpr::Or(exprs) if exprs.is_empty() =>
+            Err(CatError::InvalidExpression(format!("{:?} needs at least one operand", expr))),
+        CatExpr::And(exprs) | CatExpr::Or(exprs) => exprs.iter().try_for_each(check_expr),
+        CatExpr::Not(expr) => check_expr(expr),
+    }
+}
+
+#[test]
+fn create_cat_condi_expr_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let work = cat_service.create_cat("a", "Work", "", "").unwrap();
+    let done = cat_service.create_cat("b", "Done", "", "").unwrap();
+    let urgent = cat_service.create_cat("c", "Urgent", "", "").unwrap();
+    let escalate = cat_service.create_cat("d", "Escalate", "", "").unwrap();
+
+    let work_not_done = CatExpr::And(vec![on(&work), CatExpr::Not(Box::new(on(&done)))]);
+    let condition = cat_service.create_cat_condi_expr(work_not_done.clone(), escalate.triple_id.clone()).unwrap();
+    assert_eq!(condition.condition, work_not_done);
+    assert_ne!(condition.super_cat_id, work.triple_id);
+
+    let cats = cat_service.get_all_cats_w_conditions(graph()).unwrap();
+    assert_eq!(cats.get("d".to_string()).unwrap().condition, Some(work_not_done.clone()));
+    assert!(!cats.get("a".to_string()).unwrap().has_conditions());
+
+    // storing the same expression again changes nothing, a second condition is ORed
+    cat_service.create_cat_condi_expr(work_not_done.clone(), escalate.triple_id.clone()).unwrap();
+    cat_service.create_cat_condi(urgent.triple_id.clone(), escalate.triple_id.clone()).unwrap();
+    let cats = cat_service.get_all_cats_w_conditions(graph()).unwrap();
+    assert_eq!(cats.get("d".to_string()).unwrap().condition, Some(CatExpr::Or(vec![work_not_done, on(&urgent)])));
+
+    assert_eq!(cat_service.create_cat_condi_expr(CatExpr::Or(Vec::new()), escalate.triple_id.clone()),
+        Err(CatError::InvalidExpression("Or([]) needs at least one operand".to_string())));
+}
+
+#[test]
+fn validate_conditions_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let a = cat_service.create_cat("a", "A", "", "").unwrap();
+    let b = cat_service.create_cat("b", "B", "", "").unwrap();
+    let c = cat_service.create_cat("c", "C", "", "").unwrap();
+    let d = cat_service.create_cat("d", "D", "", "").unwrap();
+
+    cat_service.create_cat_condi(a.triple_id.clone(), b.triple_id.clone()).unwrap();
+    assert_eq!(cat_service.create_cat_condi(b.triple_id.clone(), a.triple_id.clone()),
+        Err(CatError::Cycle(vec![a.triple_id.clone(), b.triple_id.clone(), a.triple_id.clone()])));
+    assert_eq!(cat_service.create_cat_condi(c.triple_id.clone(), c.triple_id.clone()),
+        Err(CatError::Cycle(vec![c.triple_id.clone(), c.triple_id.clone()])));
+    assert_eq!(cat_service.create_cat_condi("x".to_string(), c.triple_id.clone()),
+        Err(CatError::UnknownCat("x".to_string())));
+    cat_service.create_cat_condi(d.triple_id.clone(), c.triple_id.clone()).unwrap();
+    cat_service.delete_cat(d.clone()).unwrap();
+    assert_eq!(cat_service.create_cat_condi(d.triple_id.clone(), c.triple_id.clone()),
+        Err(CatError::UnknownCat(d.triple_id.clone())));
+    assert_eq!(cat_service.validate_conditions(graph()).unwrap(),
+        vec![CatConditionIssue::CanceledCat { sub_cat_id: c.triple_id.clone(), cat_id: d.triple_id.clone() }]);
+
+    // spaces written before the checks existed can hold anything
+    for (super_cat_id, sub_cat_id) in [(b.triple_id.clone(), a.triple_id.clone()), ("x".to_string(), a.triple_id.clone())] {
+        let conditioned = cat_service.store.create_infotriple(sub_cat_id, cat_service.conditioned_tag.clone()).unwrap();
+        cat_service.store.create_infotriple(conditioned.id, super_cat_id).unwrap();
+    }
+    let issues = cat_service.validate_conditions(graph()).unwrap();
+    assert_eq!(issues.len(), 3);
+    assert!(issues.contains(&CatConditionIssue::UnknownCat { sub_cat_id: a.triple_id.clone(), cat_id: "x".to_string() }));
+    assert!(issues.iter().any(|issue|match issue {
+        CatConditionIssue::Cycle(cat_ids) => cat_ids.len() == 3 && cat_ids.contains(&a.triple_id) && cat_ids.contains(&b.triple_id),
+        _ => false,
+    }));
+}
+
+#[test]
+fn remove_cat_condi_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let a = cat_service.create_cat("a", "A", "", "").unwrap();
+    let b = cat_service.create_cat("b", "B", "", "").unwrap();
+    let c = cat_service.create_cat("c", "C", "", "").unwrap();
+    let condition_of_b = |cat_service: &mut crate::cat_service_mem::CatServiceMem| cat_service.get_all_cats_w_conditions(graph())
+        .unwrap()
+        .get("b".to_string())
+        .unwrap()
+        .condition;
+
+    let on_a = cat_service.create_cat_condi(a.triple_id.clone(), b.triple_id.clone()).unwrap();
+    let on_c = cat_service.create_cat_condi(c.triple_id.clone(), b.triple_id.clone()).unwrap();
+    assert_eq!(cat_service.get_cat_condis(graph(), b.triple_id.clone()).unwrap(), vec![on_a.clone(), on_c.clone()]);
+
+    cat_service.remove_cat_condi(&on_a).unwrap();
+    assert_eq!(condition_of_b(&mut cat_service), Some(CatExpr::Cat(c.triple_id.clone())));
+    assert_eq!(cat_service.get_cat_condis(graph(), b.triple_id.clone()).unwrap(), vec![on_c.clone()]);
+
+    let not_a = CatExpr::Not(Box::new(CatExpr::Cat(a.triple_id.clone())));
+    let replaced = cat_service.replace_cat_condi(&on_c, not_a.clone()).unwrap();
+    assert_eq!(condition_of_b(&mut cat_service), Some(not_a.clone()));
+    // replacing a condition by itself keeps it
+    let replaced = cat_service.replace_cat_condi(&replaced, not_a.clone()).unwrap();
+    assert_eq!(condition_of_b(&mut cat_service), Some(not_a));
+    assert!(cat_service.replace_cat_condi(&replaced, CatExpr::Cat(b.triple_id.clone())).is_err());
+    assert_eq!(cat_service.get_cat_condis(graph(), b.triple_id.clone()).unwrap(), vec![replaced]);
+
+    cat_service.create_cat_condi(a.triple_id.clone(), b.triple_id.clone()).unwrap();
+    assert_eq!(cat_service.clear_cat_condis(b.triple_id.clone()).unwrap().len(), 2);
+    assert_eq!(condition_of_b(&mut cat_service), None);
+    assert!(cat_service.get_cat_condis(graph(), b.triple_id.clone()).unwrap().is_empty());
+
+    // a removed condition can be created again
+    cat_service.create_cat_condi(a.triple_id.clone(), b.triple_id.clone()).unwrap();
+    assert_eq!(condition_of_b(&mut cat_service), Some(CatExpr::Cat(a.triple_id.clone())));
+}
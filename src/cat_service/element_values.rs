@@ -0,0 +1,149 @@
+use std::cmp::Ordering;
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_element::CatElement;
+use crate::model::cat_element_list::CatElementList;
+use crate::model::cat_value::CatValue;
+use crate::store::cat_store::CatStore;
+
+///
+/// The key the value of a cat element is stored under.
+///
+pub(super) const VALUE_KEY: &str = "value";
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Like create_cat_element, but records value with the tag.
+    ///
+    pub fn create_cat_element_w_value(&mut self, cat: Cat, id_to_tag: String, value: CatValue) -> Result<CatElement, CatError> {
+        let cat_element = self.create_cat_element(cat, id_to_tag)?;
+        self.set_cat_element_value(&cat_element, value)
+    }
+
+    ///
+    /// Records value with the tag cat_element represents, replacing any earlier value, and returns
+    /// the CatElement with the value set. Like properties, a text value cannot start or end with
+    /// whitespace or hold a line break.
+    ///
+    pub fn set_cat_element_value(&mut self, cat_element: &CatElement, value: CatValue) -> Result<CatElement, CatError> {
+        check_id(&cat_element.element_id)?;
+        self.set_keyed_value(cat_element.element_id.clone(), self.valued_tag.clone(), VALUE_KEY, &value)?;
+
+        let mut result = cat_element.clone();
+        result.value = Some(value);
+        Ok(result)
+    }
+
+    ///
+    /// Removes the value of the tag cat_element represents, the tag itself stays.
+    ///
+    pub fn remove_cat_element_value(&mut self, cat_element: &CatElement) -> Result<CatElement, CatError> {
+        check_id(&cat_element.element_id)?;
+        self.remove_keyed_value(cat_element.element_id.clone(), self.valued_tag.clone(), VALUE_KEY)?;
+
+        let mut result = cat_element.clone();
+        result.value = None;
+        Ok(result)
+    }
+
+    ///
+    /// Like get_cat_elements, but with the values of the elements loaded and the list sorted by
+    /// value, ascending or descending, with elements without a value last.
+    /// If min or max is given only the elements with a value between min and max, both included,
+    /// are returned.
+    ///
+    pub fn get_cat_elements_w_values(&mut self, graph_name: String, cat_id: String, min: Option<CatValue>, max: Option<CatValue>, descending: bool) -> Result<CatElementList, CatError> {
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let info_triples = self.live_info_triples(info_triples);
+        let mut values = self.get_keyed_values(&info_triples, self.valued_tag.clone())?;
+
+        let filtered = min.is_some() || max.is_some();
+        let in_range = |value: &CatValue| min.as_ref().is_none_or(|min|value >= min) && max.as_ref().is_none_or(|max|value <= max);
+
+        let mut result = CatElementList::new();
+        self.select_cat_elements(&info_triples, cat_id)?
+            .into_iter()
+            .map(|mut cat_element|{
+                cat_element.value = values
+                    .remove(&cat_element.element_id)
+                    .and_then(|mut values|values.remove(VALUE_KEY));
+                cat_element
+            })
+            .filter(|cat_element|!filtered || cat_element.value.as_ref().is_some_and(in_range))
+            .for_each(|cat_element|result.add(cat_element));
+
+        result.sort_by(|a, b|match (&a.value, &b.value) {
+            (Some(a), Some(b)) if descending => b.cmp(a),
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(..), None) => Ordering::Less,
+            (None, Some(..)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+        Ok(result)
+    }
+}
+
+#[test]
+fn cat_element_value_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let effort = cat_service.create_cat("a", "Effort", "", "").unwrap();
+    let task1 = cat_service.create_cat_element_w_value(effort.clone(), "task1".to_string(), CatValue::Integer(3)).unwrap();
+    assert_eq!(task1.value, Some(CatValue::Integer(3)));
+    cat_service.create_cat_element_w_value(effort.clone(), "task2".to_string(), CatValue::Decimal(0.5)).unwrap();
+    let task3 = cat_service.create_cat_element(effort.clone(), "task3".to_string()).unwrap();
+    cat_service.create_cat_element_w_value(effort.clone(), "task4".to_string(), CatValue::Integer(8)).unwrap();
+
+    let all = cat_service.get_cat_elements_w_values(graph(), "a".to_string(), None, None, false).unwrap();
+    assert_eq!(all.clone().into_iter().next().unwrap().value, Some(CatValue::Decimal(0.5)));
+    assert_eq!(element_points(all), vec!["task2", "task1", "task4", "task3"]);
+    assert_eq!(element_points(cat_service.get_cat_elements_w_values(graph(), "a".to_string(), None, None, true).unwrap()),
+        vec!["task4", "task1", "task2", "task3"]);
+    assert_eq!(element_points(cat_service.get_cat_elements_w_values(graph(), "a".to_string(), Some(CatValue::Integer(1)), None, false).unwrap()),
+        vec!["task1", "task4"]);
+    assert!(cat_service.get_cat_elements(graph(), "a".to_string()).unwrap().into_iter().all(|ce|ce.value.is_none()));
+
+    cat_service.set_cat_element_value(&task3, CatValue::Integer(1)).unwrap();
+    cat_service.set_cat_element_value(&task1, CatValue::Integer(13)).unwrap();
+    cat_service.remove_cat_element_value(&task1).unwrap();
+    assert_eq!(element_points(cat_service.get_cat_elements_w_values(graph(), "a".to_string(), None, Some(CatValue::Integer(5)), false).unwrap()),
+        vec!["task2", "task3"]);
+    assert_eq!(element_points(cat_service.get_cat_elements_w_values(graph(), "a".to_string(), None, None, false).unwrap()),
+        vec!["task2", "task3", "task4", "task1"]);
+
+    // integers too large for an f64 are filtered exactly
+    let big = cat_service.create_cat("b", "Big", "", "").unwrap();
+    let limit = 1_i64 << 53;
+    cat_service.create_cat_element_w_value(big.clone(), "at".to_string(), CatValue::Integer(limit)).unwrap();
+    cat_service.create_cat_element_w_value(big.clone(), "above".to_string(), CatValue::Integer(limit + 1)).unwrap();
+    assert_eq!(element_points(cat_service.get_cat_elements_w_values(graph(), "b".to_string(), None, Some(CatValue::Decimal(limit as f64)), false).unwrap()),
+        vec!["at"]);
+    assert_eq!(element_points(cat_service.get_cat_elements_w_values(graph(), "b".to_string(), None, None, true).unwrap()),
+        vec!["above", "at"]);
+}
+
+#[test]
+fn cat_element_value_fs_test() {
+    use crate::cat_service::test_tools::*;
+
+    let (mut cat_service, _guard) = scratch_service_fs("cat_element_value_fs_test");
+    let effort = cat_service.create_cat("value_fs_a", "Effort", "", "").unwrap();
+    cat_service.create_cat_element_w_value(effort.clone(), "value_fs_task1".to_string(), CatValue::Integer(3)).unwrap();
+    let task2 = cat_service.create_cat_element_w_value(effort.clone(), "value_fs_task2".to_string(), CatValue::Decimal(0.5)).unwrap();
+    cat_service.create_cat_element_w_value(effort.clone(), "value_fs_task3".to_string(), CatValue::from("a lot")).unwrap();
+    cat_service.set_cat_element_value(&task2, CatValue::Integer(5)).unwrap();
+
+    let elements = cat_service.get_cat_elements_w_values(graph(), "value_fs_a".to_string(), None, None, false).unwrap();
+    let values: Vec<Option<CatValue>> = elements.clone().into_iter().map(|ce|ce.value).collect();
+    assert_eq!(values, vec![Some(CatValue::Integer(3)), Some(CatValue::Integer(5)), Some(CatValue::from("a lot"))]);
+    assert_eq!(element_points(elements), vec!["value_fs_task1", "value_fs_task2", "value_fs_task3"]);
+
+    cat_service.remove_cat_element_value(&task2).unwrap();
+    let elements = cat_service.get_cat_elements_w_values(graph(), "value_fs_a".to_string(), Some(CatValue::Integer(0)), None, false).unwrap();
+    assert_eq!(element_points(elements), vec!["value_fs_task1", "value_fs_task3"]);
+}
@@ -0,0 +1,286 @@
+use std::collections::{HashMap, HashSet};
+
+use infograph::types::Point;
+use infograph::InfoTriple;
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, MAIN_TABLE, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_facet::CatFacet;
+use crate::model::cat_list::CatList;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Creates a facet from a descriptor note and a facet triple and returns it. The facet has no
+    /// members until cats are added with add_cat_to_facet.
+    /// Fails with CatError::InvalidValue if facet_point is a cat or a facet already, deleted ones
+    /// included, like create_cat.
+    ///
+    pub fn create_cat_facet(&mut self, facet_point: &str, facet_name: &str, facet_desc: &str) -> Result<CatFacet, CatError> {
+        check_id(facet_point)?;
+        let info_triples = self.store.get_all_info_triples_from_info_table(MAIN_TABLE.to_string())?;
+        if self.get_all_cat_points(&info_triples).contains(facet_point) {
+            return Err(CatError::InvalidValue(format!("{} is a cat already", facet_point)));
+        }
+        if info_triples.iter().any(|it|it.other_half(self.facet_tag.clone()).is_ok_and(|point|point == facet_point)) {
+            return Err(CatError::InvalidValue(format!("{} is a facet already", facet_point)));
+        }
+
+        let desc = self.store.create_desc(facet_point.to_string(),
+                                          facet_name.to_string(), "".to_string(), facet_desc.to_string())?;
+        let it = self.store.create_infotriple(facet_point.to_string(), self.facet_tag.clone())?;
+
+        Ok(CatFacet {
+            triple_id: it.id,
+            point: desc.point,
+            desc_id: desc.desc_id,
+            name: desc.name,
+            description: desc.description,
+            cat_ids: Vec::new(),
+            ordinal: false,
+        })
+    }
+
+    ///
+    /// Cancels the facet and returns it. Its member cats and their elements are kept.
+    ///
+    pub fn delete_cat_facet(&mut self, facet: CatFacet) -> Result<CatFacet, CatError> {
+        check_id(&facet.triple_id)?;
+        self.cancel_infotriple(facet.triple_id.clone())?;
+        Ok(facet)
+    }
+
+    ///
+    /// Loads and returns the facets of graph_name ordered by point, each with its member cats.
+    ///
+    pub fn get_all_cat_facets(&mut self, graph_name: String) -> Result<Vec<CatFacet>, CatError> {
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let info_triples = self.live_info_triples(info_triples);
+        let cat_points = self.get_cat_points(&info_triples);
+        let mut members = self.get_decoration_values(&info_triples, self.facet_tag.clone());
+        let mut orders = self.get_facet_orders(&info_triples);
+
+        let mut facets: Vec<(String, Point)> = info_triples
+            .iter()
+            .filter_map(|it|it.other_half(self.facet_tag.clone()).ok().map(|point|(it.id.clone(), point)))
+            .collect();
+        facets.sort_by(|a, b|a.1.cmp(&b.1));
+        facets.dedup();
+
+        let descs = self.store.get_descs_hashmap_for_list(facets.iter().map(|(_, point)|point.clone()).collect())?;
+        facets
+            .into_iter()
+            .map(|(triple_id, point)|{
+                let desc = descs.get(&point)
+                    .ok_or_else(|| CatError::MissingDescriptor(point.clone()))?;
+                let mut cat_ids: Vec<String> = Vec::new();
+                members.remove(&point)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|cat_id|cat_points.contains_key(cat_id))
+                    .for_each(|cat_id|{
+                        if !cat_ids.contains(&cat_id) {
+                            cat_ids.push(cat_id);
+                        }
+                    });
+                let order = orders.remove(&triple_id);
+                let ordinal = order.is_some();
+                if let Some(order) = order {
+                    let mut ordered: Vec<String> = order.into_iter().filter(|cat_id|cat_ids.contains(cat_id)).collect();
+                    cat_ids.retain(|cat_id|!ordered.contains(cat_id));
+                    ordered.append(&mut cat_ids);
+                    cat_ids = ordered;
+                }
+                Ok(CatFacet {
+                    triple_id,
+                    point,
+                    desc_id: desc.desc_id.clone(),
+                    name: desc.name.clone(),
+                    description: desc.description.clone(),
+                    cat_ids,
+                    ordinal,
+                })
+            })
+            .collect()
+    }
+
+    ///
+    /// Adds cat to the facet and returns the updated facet. Elements already tagged with more than
+    /// one member keep their tags, get_cat_facet_values picks the most recent one.
+    /// Fails with CatError::UnknownCat if cat is not a cat currently in the space.
+    ///
+    pub fn add_cat_to_facet(&mut self, facet: CatFacet, cat: Cat) -> Result<CatFacet, CatError> {
+        check_id(&facet.triple_id)?;
+        check_id(&cat.triple_id)?;
+        let (_, cats) = self.load_live(MAIN_TABLE.to_string())?;
+        if cats.get_by_triple_id(cat.triple_id.clone()).is_none() {
+            return Err(CatError::UnknownCat(cat.triple_id));
+        }
+        self.store.create_infotriple(cat.triple_id.clone(), facet.triple_id.clone())?;
+
+        let mut result = facet;
+        if !result.cat_ids.contains(&cat.triple_id) {
+            result.cat_ids.push(cat.triple_id);
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Removes cat from the facet by canceling the triple pairing them and returns the updated facet.
+    ///
+    pub fn remove_cat_from_facet(&mut self, facet: CatFacet, cat: Cat) -> Result<CatFacet, CatError> {
+        check_id(&facet.triple_id)?;
+        check_id(&cat.triple_id)?;
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(MAIN_TABLE.to_string())?;
+        let member = self.live_info_triples(info_triples)
+            .into_iter()
+            .find(|it|it.other_half(facet.triple_id.clone()).is_ok_and(|cat_id|cat_id == cat.triple_id));
+        if let Some(member) = member {
+            self.cancel_infotriple(member.id)?;
+        }
+
+        let mut result = facet;
+        result.cat_ids.retain(|cat_id|*cat_id != cat.triple_id);
+        Ok(result)
+    }
+
+    ///
+    /// Returns the current value of every element tagged with a member of the facet, meaning the
+    /// member cat the element has been tagged with most recently, keyed by the element's point.
+    ///
+    pub fn get_cat_facet_values(&mut self, graph_name: String, facet: &CatFacet) -> Result<HashMap<Point, Cat>, CatError> {
+
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        Ok(self.get_facet_tags(&info_triples, &cats, facet)
+            .into_iter()
+            .map(|(point, (_, cat))|(point, cat))
+            .collect())
+    }
+
+    ///
+    /// Returns for every element tagged with a member of facet the triple tagging it with the member
+    /// it has been tagged with most recently and that member, keyed by the element's point.
+    ///
+    pub(super) fn get_facet_tags(&self, info_triples: &[InfoTriple], cats: &CatList, facet: &CatFacet) -> HashMap<Point, (InfoTriple, Cat)> {
+
+        let members: HashMap<Point, Cat> = facet.cat_ids
+            .iter()
+            .filter_map(|cat_id|cats.get_by_triple_id(cat_id.clone()))
+            .map(|cat|(cat.point.clone(), cat))
+            .collect();
+
+        let mut result: HashMap<Point, (InfoTriple, Cat)> = HashMap::new();
+        info_triples
+            .iter()
+            .filter(|it|it.id1 != self.cat_tag && it.id2 != self.cat_tag)
+            .for_each(|it|{
+                [(&it.id1, &it.id2), (&it.id2, &it.id1)]
+                    .iter()
+                    .filter_map(|(cat_point, point)|members.get(*cat_point).map(|cat|(point, cat)))
+                    .for_each(|(point, cat)|{
+                        result.insert(point.to_string(), (it.clone(), cat.clone()));
+                    });
+            });
+
+        result
+    }
+
+    ///
+    /// Removes the tags of point with the cats sharing a facet with cat. Called after point has
+    /// been tagged with cat.
+    ///
+    pub(super) fn cancel_facet_siblings(&mut self, cat: &Cat, point: &str) -> Result<(), CatError> {
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(MAIN_TABLE.to_string())?;
+        let info_triples = self.live_info_triples(info_triples);
+        let sibling_points = self.get_facet_sibling_points(&info_triples, cat);
+
+        for it in info_triples.iter() {
+            let sibling_tag = (it.id1 == point && sibling_points.contains(&it.id2))
+                || (it.id2 == point && sibling_points.contains(&it.id1));
+            if sibling_tag {
+                self.cancel_infotriple(it.id.clone())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the points of the cats sharing a facet with cat in info_triples.
+    ///
+    pub(super) fn get_facet_sibling_points(&self, info_triples: &[InfoTriple], cat: &Cat) -> HashSet<Point> {
+        let cat_points = self.get_cat_points(info_triples);
+        self.get_decoration_values(info_triples, self.facet_tag.clone())
+            .into_values()
+            .filter(|cat_ids|cat_ids.contains(&cat.triple_id))
+            .flatten()
+            .filter(|cat_id|*cat_id != cat.triple_id)
+            .filter_map(|cat_id|cat_points.get(&cat_id).cloned())
+            .collect()
+    }
+
+    ///
+    /// Returns the points of the cats in info_triples keyed by the cats' triple IDs.
+    ///
+    fn get_cat_points(&self, info_triples: &[InfoTriple]) -> HashMap<String, Point> {
+        info_triples
+            .iter()
+            .filter_map(|it|it.other_half(self.cat_tag.clone()).ok().map(|point|(it.id.clone(), point)))
+            .collect()
+    }
+}
+
+#[test]
+fn facet_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let todo = cat_service.create_cat("a", "Todo", "", "").unwrap();
+    let doing = cat_service.create_cat("b", "Doing", "", "").unwrap();
+    let done = cat_service.create_cat("c", "Done", "", "").unwrap();
+    let urgent = cat_service.create_cat("d", "Urgent", "", "").unwrap();
+
+    let status = cat_service.create_cat_facet("status", "Status", "where a task stands").unwrap();
+    let status = cat_service.add_cat_to_facet(status, todo.clone()).unwrap();
+    let status = cat_service.add_cat_to_facet(status, doing.clone()).unwrap();
+    let status = cat_service.add_cat_to_facet(status, done.clone()).unwrap();
+    assert_eq!(cat_service.get_all_cat_facets(graph()).unwrap(), vec![status.clone()]);
+    assert_eq!(cat_service.create_cat_facet("status", "State", "").unwrap_err(),
+        CatError::InvalidValue("status is a facet already".to_string()));
+    assert_eq!(cat_service.create_cat_facet("a", "State", "").unwrap_err(),
+        CatError::InvalidValue("a is a cat already".to_string()));
+    let gone = cat_service.create_cat("e", "Gone", "", "").unwrap();
+    cat_service.delete_cat(gone.clone()).unwrap();
+    assert_eq!(cat_service.add_cat_to_facet(status.clone(), gone.clone()).unwrap_err(), CatError::UnknownCat(gone.triple_id));
+    assert_eq!(cat_service.get_all_cat_facets(graph()).unwrap(), vec![status.clone()]);
+
+    cat_service.create_cat_element(todo.clone(), "task1".to_string()).unwrap();
+    cat_service.create_cat_element(urgent.clone(), "task1".to_string()).unwrap();
+    cat_service.create_cat_element(todo.clone(), "task2".to_string()).unwrap();
+    cat_service.create_cat_element(doing.clone(), "task1".to_string()).unwrap();
+
+    assert_eq!(cat_points(cat_service.get_cats_for_point(graph(), "task1".to_string()).unwrap()), vec!["b", "d"]);
+    let values = cat_service.get_cat_facet_values(graph(), &status).unwrap();
+    assert_eq!(values.len(), 2);
+    assert_eq!(values.get("task1").unwrap().name, "Doing");
+    assert_eq!(values.get("task2").unwrap().name, "Todo");
+
+    // back to a value the element had before
+    cat_service.create_cat_element(todo.clone(), "task1".to_string()).unwrap();
+    assert_eq!(cat_points(cat_service.get_cats_for_point(graph(), "task1".to_string()).unwrap()), vec!["a", "d"]);
+
+    let status = cat_service.remove_cat_from_facet(status, done.clone()).unwrap();
+    cat_service.create_cat_element(done.clone(), "task1".to_string()).unwrap();
+    assert_eq!(cat_points(cat_service.get_cats_for_point(graph(), "task1".to_string()).unwrap()), vec!["c", "a", "d"]);
+    assert_eq!(cat_service.get_all_cat_facets(graph()).unwrap()[0].cat_ids, status.cat_ids);
+
+    cat_service.delete_cat_facet(status).unwrap();
+    assert!(cat_service.get_all_cat_facets(graph()).unwrap().is_empty());
+    cat_service.create_cat_element(doing.clone(), "task1".to_string()).unwrap();
+    assert_eq!(cat_points(cat_service.get_cats_for_point(graph(), "task1".to_string()).unwrap()), vec!["b", "c", "a", "d"]);
+}
@@ -0,0 +1,212 @@
+use std::collections::{HashMap, HashSet};
+
+use infograph::{InfoTable, InfoTriple};
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, MAIN_TABLE, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_element_list::CatElementList;
+use crate::model::cat_list::CatList;
+use crate::model::cat_parent::CatParent;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Makes child a sub-category of parent and returns the resulting CatParent.
+    /// A cat can have more than one parent, but a cat can never become its own ancestor.
+    ///
+    pub fn create_cat_parent(&mut self, parent: Cat, child: Cat) -> Result<CatParent, CatError> {
+        check_id(&parent.triple_id)?;
+        check_id(&child.triple_id)?;
+
+        let (info_triples, cats) = self.load_live(MAIN_TABLE.to_string())?;
+        for cat in [&parent, &child] {
+            if cats.get_by_triple_id(cat.triple_id.clone()).is_none() {
+                return Err(CatError::UnknownCat(cat.triple_id.clone()));
+            }
+        }
+        let parents = self.get_parent_ids(&info_triples, &cats);
+        if parent.triple_id == child.triple_id || ancestor_ids(&parents, &parent.triple_id).contains(&child.triple_id) {
+            return Err(CatError::Cycle(vec![child.triple_id, parent.triple_id]));
+        }
+
+        let decoration = self.store.create_infotriple(child.triple_id.clone(), self.parent_tag.clone())?;
+        let result = self.store.create_infotriple(decoration.id.clone(), parent.triple_id.clone())?;
+
+        Ok(CatParent {
+            triple_id: result.id,
+            parent_decoration_id: decoration.id,
+            parent_id: parent.triple_id,
+            child_id: child.triple_id,
+        })
+    }
+
+    ///
+    /// Removes a parent/child relation by canceling its triple and returns the removed CatParent.
+    ///
+    pub fn remove_cat_parent(&mut self, cat_parent: &CatParent) -> Result<CatParent, CatError> {
+        check_id(&cat_parent.triple_id)?;
+        self.cancel_infotriple(cat_parent.triple_id.clone())?;
+        Ok(cat_parent.clone())
+    }
+
+    ///
+    /// Returns the direct parents of cat.
+    ///
+    pub fn get_cat_parents(&mut self, graph_name: String, cat: Cat) -> Result<CatList, CatError> {
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let parents = self.get_parent_ids(&info_triples, &cats);
+        Ok(select_cats(&cats, parents.get(&cat.triple_id).cloned().unwrap_or_default()))
+    }
+
+    ///
+    /// Returns the direct children of cat.
+    ///
+    pub fn get_cat_children(&mut self, graph_name: String, cat: Cat) -> Result<CatList, CatError> {
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let children = invert(&self.get_parent_ids(&info_triples, &cats));
+        Ok(select_cats(&cats, children.get(&cat.triple_id).cloned().unwrap_or_default()))
+    }
+
+    ///
+    /// Returns the parents of cat, their parents and so on.
+    ///
+    pub fn get_cat_ancestors(&mut self, graph_name: String, cat: Cat) -> Result<CatList, CatError> {
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let parents = self.get_parent_ids(&info_triples, &cats);
+        Ok(select_cats(&cats, ancestor_ids(&parents, &cat.triple_id)))
+    }
+
+    ///
+    /// Returns the children of cat, their children and so on.
+    ///
+    pub fn get_cat_descendants(&mut self, graph_name: String, cat: Cat) -> Result<CatList, CatError> {
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let children = invert(&self.get_parent_ids(&info_triples, &cats));
+        Ok(select_cats(&cats, ancestor_ids(&children, &cat.triple_id)))
+    }
+
+    ///
+    /// Like get_cat_elements, but also includes the elements tagged with any descendant of the cat.
+    /// A point tagged with more than one of the cats is only listed once, preferring the tag of the
+    /// cat cat_id itself.
+    ///
+    pub fn get_cat_elements_w_descendants(&mut self, graph_name: String, cat_id: String) -> Result<CatElementList, CatError> {
+        let (info_triples, cats) = self.load_live(graph_name.clone())?;
+        let cat = cats.get(cat_id.clone())
+            .ok_or_else(|| CatError::UnknownCat(cat_id.clone()))?;
+        let children = invert(&self.get_parent_ids(&info_triples, &cats));
+
+        let mut cat_ids = vec![cat.point.clone()];
+        cat_ids.extend(select_cats(&cats, ancestor_ids(&children, &cat.triple_id))
+            .into_iter()
+            .map(|cat|cat.point));
+
+        let mut info_table = InfoTable::from_iter(info_triples);
+        let mut points: HashSet<String> = HashSet::new();
+        let mut result = CatElementList::new();
+        for cat_id in cat_ids {
+            let element_table = info_table.get_neighbors_except_decorated_and_not(cat_id.clone(), self.canceled_tag.clone(), self.cat_tag.clone());
+            for cat_element in self.populate_cat_element_list(cat_id, element_table)? {
+                if points.insert(cat_element.point.clone()) {
+                    result.add(cat_element);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    ///
+    /// Returns the triple IDs of the parents of each cat with parents, keyed by the cat's triple ID.
+    /// Relations involving cats not in cats are left out.
+    ///
+    pub(super) fn get_parent_ids(&self, info_triples: &[InfoTriple], cats: &CatList) -> HashMap<String, Vec<String>> {
+        let cat_ids: HashSet<String> = cats.cats.values().map(|cat|cat.triple_id.clone()).collect();
+        self.get_decoration_values(info_triples, self.parent_tag.clone())
+            .into_iter()
+            .filter(|(child_id, _)|cat_ids.contains(child_id))
+            .map(|(child_id, mut parent_ids)|{
+                parent_ids.retain(|parent_id|cat_ids.contains(parent_id));
+                parent_ids.sort();
+                parent_ids.dedup();
+                (child_id, parent_ids)
+            })
+            .collect()
+    }
+}
+
+///
+/// Follows the relations in links from id and returns every ID reached, not including id itself
+/// unless there is a cycle.
+///
+fn ancestor_ids(links: &HashMap<String, Vec<String>>, id: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut todo: Vec<String> = links.get(id).cloned().unwrap_or_default();
+    while let Some(next) = todo.pop() {
+        if visited.insert(next.clone()) {
+            todo.extend(links.get(&next).cloned().unwrap_or_default());
+            result.push(next);
+        }
+    }
+    result
+}
+
+fn invert(links: &HashMap<String, Vec<String>>) -> HashMap<String, Vec<String>> {
+    let mut result: HashMap<String, Vec<String>> = HashMap::new();
+    links
+        .iter()
+        .for_each(|(from, tos)|{
+            tos.iter().for_each(|to|result.entry(to.clone()).or_default().push(from.clone()));
+        });
+    result
+}
+
+fn select_cats(cats: &CatList, triple_ids: Vec<String>) -> CatList {
+    let mut result = CatList::new();
+    triple_ids
+        .into_iter()
+        .filter_map(|triple_id|cats.get_by_triple_id(triple_id))
+        .for_each(|cat|result.add(cat));
+    result
+}
+
+#[test]
+fn hierarchy_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let programming = cat_service.create_cat("a", "Programming", "", "").unwrap();
+    let rust = cat_service.create_cat("b", "Rust", "", "").unwrap();
+    let async_rust = cat_service.create_cat("c", "Async", "", "").unwrap();
+    let cooking = cat_service.create_cat("d", "Cooking", "", "").unwrap();
+    cat_service.create_cat_parent(programming.clone(), rust.clone()).unwrap();
+    let async_parent = cat_service.create_cat_parent(rust.clone(), async_rust.clone()).unwrap();
+
+    assert_eq!(cat_points(cat_service.get_cat_children(graph(), programming.clone()).unwrap()), vec!["b"]);
+    assert_eq!(cat_points(cat_service.get_cat_parents(graph(), async_rust.clone()).unwrap()), vec!["b"]);
+    assert_eq!(cat_points(cat_service.get_cat_ancestors(graph(), async_rust.clone()).unwrap()), vec!["a", "b"]);
+    assert_eq!(cat_points(cat_service.get_cat_descendants(graph(), programming.clone()).unwrap()), vec!["c", "b"]);
+    assert!(cat_service.get_cat_descendants(graph(), cooking.clone()).unwrap().is_empty());
+
+    assert_eq!(cat_service.create_cat_parent(async_rust.clone(), programming.clone()).unwrap_err(),
+        CatError::Cycle(vec![programming.triple_id.clone(), async_rust.triple_id.clone()]));
+    assert!(cat_service.create_cat_parent(rust.clone(), rust.clone()).is_err());
+
+    cat_service.create_cat_element(programming.clone(), "note1".to_string()).unwrap();
+    cat_service.create_cat_element(rust.clone(), "note1".to_string()).unwrap();
+    cat_service.create_cat_element(async_rust.clone(), "note2".to_string()).unwrap();
+    cat_service.create_cat_element(cooking.clone(), "note3".to_string()).unwrap();
+    let elements = cat_service.get_cat_elements_w_descendants(graph(), "a".to_string()).unwrap();
+    let mut elements = elements.into_iter();
+    let element = elements.next().unwrap();
+    assert_eq!((element.point, element.cat_id), ("note1".to_string(), "a".to_string()));
+    let element = elements.next().unwrap();
+    assert_eq!((element.point, element.cat_id), ("note2".to_string(), "c".to_string()));
+    assert!(elements.next().is_none());
+
+    cat_service.remove_cat_parent(&async_parent).unwrap();
+    assert_eq!(cat_points(cat_service.get_cat_descendants(graph(), programming.clone()).unwrap()), vec!["b"]);
+}
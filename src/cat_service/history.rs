@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet};
+
+use infograph::InfoTriple;
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_element_list::CatElementList;
+use crate::model::cat_event::{CatEvent, CatEventKind};
+use crate::model::cat_list::CatList;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Returns the history of cat, oldest first: when it was created, renamed, deleted, restored,
+    /// given or relieved of conditions and merged into another cat, and when points were tagged
+    /// with it or untagged.
+    /// Writes not changing anything, like tagging a point already tagged, are left out. So are
+    /// pairings of the cat's point with a tag or the ID of an earlier triple, which decorate the
+    /// point rather than tag anything. A pairing with the point of a cat created before cannot tell
+    /// which of the two cats tagged the other, so it counts for neither.
+    ///
+    pub fn get_cat_history(&mut self, graph_name: String, cat: &Cat) -> Result<Vec<CatEvent>, CatError> {
+        check_id(&cat.triple_id)?;
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let stamps = self.get_write_stamps(&info_triples)?;
+        let nodes = self.get_expr_nodes(&info_triples);
+        let decoration_id = |tag: &String| -> Option<String> {
+            info_triples
+                .iter()
+                .find(|it|it.other_half(tag.clone()).is_ok_and(|decorated|decorated == cat.triple_id))
+                .map(|it|it.id.clone())
+        };
+        let conditioned_id = decoration_id(&self.conditioned_tag);
+        let revised_id = decoration_id(&self.revised_tag);
+        let merged_id = decoration_id(&self.merged_tag);
+        let paired_with = |it: &InfoTriple, id: &Option<String>| -> Option<String> {
+            id.as_ref().and_then(|id|it.other_half(id.clone()).ok())
+        };
+
+        // what canceling a triple means, keyed by the triple's ID
+        let mut cancellations: HashMap<String, CatEventKind> = HashMap::new();
+        let mut live: HashSet<String> = HashSet::new();
+        let mut revision_points: Vec<String> = Vec::new();
+        // what the cat's point may not be tagged with at the point of the pairing
+        let mut triple_ids: HashSet<&String> = HashSet::new();
+        let mut cat_points: HashSet<String> = HashSet::new();
+        let is_element = |point: &String, triple_ids: &HashSet<&String>, cat_points: &HashSet<String>| {
+            !self.is_tag(point) && !triple_ids.contains(point) && !cat_points.contains(point)
+        };
+        let mut result: Vec<CatEvent> = Vec::new();
+        for (it, stamp) in info_triples.iter().zip(stamps) {
+            let kind = if it.id == cat.triple_id {
+                let created = cancellations.insert(it.id.clone(), CatEventKind::Canceled).is_some();
+                match live.insert(it.id.clone()) {
+                    true if created => Some(CatEventKind::Restored),
+                    true => Some(CatEventKind::Created),
+                    false => None,
+                }
+            } else if let Ok(canceled_id) = it.other_half(self.canceled_tag.clone()) {
+                match live.remove(&canceled_id) {
+                    true => cancellations.get(&canceled_id).cloned(),
+                    false => None,
+                }
+            } else if let Some(root_id) = paired_with(it, &conditioned_id) {
+                let condition = nodes.read(&root_id);
+                cancellations.insert(it.id.clone(), CatEventKind::Unconditioned(condition.clone()));
+                live.insert(it.id.clone()).then_some(CatEventKind::Conditioned(condition))
+            } else if let Some(revision_point) = paired_with(it, &revised_id) {
+                revision_points.push(revision_point.clone());
+                Some(CatEventKind::Renamed(revision_point))
+            } else if let Some(target_id) = paired_with(it, &merged_id) {
+                Some(CatEventKind::Merged(target_id))
+            } else if let Some(point) = it.other_half(cat.point.clone()).ok().filter(|point|is_element(point, &triple_ids, &cat_points)) {
+                cancellations.insert(it.id.clone(), CatEventKind::Untagged(point.clone()));
+                live.insert(it.id.clone()).then_some(CatEventKind::Tagged(point))
+            } else {
+                None
+            };
+
+            if let Some(kind) = kind {
+                result.push(CatEvent { kind, triple_id: it.id.clone(), stamp });
+            }
+            if let Ok(cat_point) = it.other_half(self.cat_tag.clone()) {
+                cat_points.insert(cat_point);
+            }
+            triple_ids.insert(&it.id);
+        }
+
+        let descs = self.store.get_descs_hashmap_for_list(revision_points)?;
+        for event in result.iter_mut() {
+            if let CatEventKind::Renamed(revision_point) = &event.kind {
+                let desc = descs.get(revision_point)
+                    .ok_or_else(|| CatError::MissingDescriptor(revision_point.to_string()))?;
+                event.kind = CatEventKind::Renamed(desc.name.clone());
+            }
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Like get_all_cats, but returns the cats as they were at timestamp, in milliseconds since the
+    /// Unix epoch. The descriptor data of the cats is the latest revision made by then.
+    ///
+    pub fn get_all_cats_as_of(&mut self, graph_name: String, timestamp: u64) -> Result<CatList, CatError> {
+        let info_triples = self.info_triples_as_of(graph_name, timestamp)?;
+        Ok(self.load_live_from(info_triples)?.1)
+    }
+
+    ///
+    /// Like get_cat_elements, but returns the elements tagged with the cat cat_id at timestamp, in
+    /// milliseconds since the Unix epoch. Fails with CatError::UnknownCat if cat_id was no cat by
+    /// then.
+    ///
+    pub fn get_cat_elements_as_of(&mut self, graph_name: String, cat_id: String, timestamp: u64) -> Result<CatElementList, CatError> {
+        let info_triples = self.info_triples_as_of(graph_name, timestamp)?;
+        let info_triples = self.live_info_triples(info_triples);
+        self.select_cat_elements(&info_triples, cat_id)
+    }
+
+    ///
+    /// Returns the info triples of graph_name appended up to timestamp, cutting the info table right
+    /// before the first write stamped later.
+    /// Writes without a stamp of their own, like display orders or writes from before stamps were
+    /// recorded, are placed in time by the stamped writes following them.
+    ///
+    fn info_triples_as_of(&mut self, graph_name: String, timestamp: u64) -> Result<Vec<InfoTriple>, CatError> {
+        let mut info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let stamps = self.get_write_stamps(&info_triples)?;
+        if let Some(cut) = stamps.iter().position(|stamp|stamp.as_ref().is_some_and(|stamp|stamp.time > timestamp)) {
+            info_triples.truncate(cut);
+        }
+        Ok(info_triples)
+    }
+}
+
+#[test]
+fn cat_history_test() {
+    use std::thread;
+    use std::time::Duration;
+    use crate::cat_service::test_tools::*;
+    use crate::model::cat_expr::CatExpr;
+
+    let mut cat_service = scratch_service();
+    let pause = || thread::sleep(Duration::from_millis(2));
+    cat_service.set_actor("alice").unwrap();
+    let todo = cat_service.create_cat("a", "Todo", "", "").unwrap();
+    let done = cat_service.create_cat("b", "Done", "", "").unwrap();
+    let note1 = cat_service.create_cat_element(todo.clone(), "note1".to_string()).unwrap();
+    // tagging again changes nothing
+    cat_service.create_cat_element(todo.clone(), "note1".to_string()).unwrap();
+    let before = note1.stamp.clone().unwrap().time;
+    pause();
+
+    cat_service.update_cat(todo.clone(), "Open", "", "").unwrap();
+    cat_service.create_cat_element(todo.clone(), "note2".to_string()).unwrap();
+    let condition = cat_service.create_cat_condi(done.triple_id.clone(), todo.triple_id.clone()).unwrap();
+    cat_service.remove_cat_element(&note1).unwrap();
+    cat_service.remove_cat_condi(&condition).unwrap();
+    cat_service.set_actor("bob").unwrap();
+    cat_service.delete_cat(todo.clone()).unwrap();
+    let restored = cat_service.restore_cat(todo.clone()).unwrap();
+
+    let history = cat_service.get_cat_history(graph(), &todo).unwrap();
+    let kinds: Vec<CatEventKind> = history.iter().map(|event|event.kind.clone()).collect();
+    let on_done = CatExpr::Cat(done.triple_id.clone());
+    assert_eq!(kinds, vec![
+        CatEventKind::Created,
+        CatEventKind::Tagged("note1".to_string()),
+        CatEventKind::Renamed("Open".to_string()),
+        CatEventKind::Tagged("note2".to_string()),
+        CatEventKind::Conditioned(on_done.clone()),
+        CatEventKind::Untagged("note1".to_string()),
+        CatEventKind::Unconditioned(on_done),
+        CatEventKind::Canceled,
+        CatEventKind::Restored,
+    ]);
+    assert_eq!(history[0].stamp, todo.stamp);
+    assert_eq!(history[7].stamp.clone().unwrap().actor, "bob");
+    assert_eq!(history[8].stamp, restored.stamp);
+
+    assert_eq!(cat_names(cat_service.get_all_cats_as_of(graph(), before).unwrap()), vec!["Done", "Todo"]);
+    assert_eq!(element_points(cat_service.get_cat_elements_as_of(graph(), "a".to_string(), before).unwrap()), vec!["note1"]);
+    assert_eq!(cat_names(cat_service.get_all_cats(graph()).unwrap()), vec!["Done", "Open"]);
+    assert_eq!(element_points(cat_service.get_cat_elements(graph(), "a".to_string()).unwrap()), vec!["note2"]);
+    assert!(cat_service.get_all_cats_as_of(graph(), 0).unwrap().into_iter().next().is_none());
+    assert_eq!(cat_service.get_cat_elements_as_of(graph(), "a".to_string(), 0).unwrap_err(), CatError::UnknownCat("a".to_string()));
+}
+
+#[test]
+fn cat_history_point_tagged_test() {
+    use crate::cat_service::test_tools::*;
+    use crate::model::cat_value::CatValue;
+
+    let mut cat_service = scratch_service();
+    let todo = cat_service.create_cat("a", "Todo", "", "").unwrap();
+    let done = cat_service.create_cat("b", "Done", "", "").unwrap();
+    cat_service.create_cat_element(todo.clone(), "note1".to_string()).unwrap();
+    // the point of todo tagged with done and decorated with a value
+    cat_service.create_cat_element(done.clone(), todo.point.clone()).unwrap();
+    let property_tag = cat_service.property_tag.clone();
+    cat_service.set_keyed_value(todo.point.clone(), property_tag, "size", &CatValue::Integer(3)).unwrap();
+    // a point tagged before it became a cat is still an element
+    cat_service.create_cat_element(todo.clone(), "c".to_string()).unwrap();
+    cat_service.create_cat("c", "Later", "", "").unwrap();
+
+    let kinds = |history: Vec<CatEvent>| -> Vec<CatEventKind> { history.into_iter().map(|event|event.kind).collect() };
+    assert_eq!(kinds(cat_service.get_cat_history(graph(), &todo).unwrap()), vec![
+        CatEventKind::Created,
+        CatEventKind::Tagged("note1".to_string()),
+        CatEventKind::Tagged("c".to_string()),
+    ]);
+    assert_eq!(kinds(cat_service.get_cat_history(graph(), &done).unwrap()), vec![CatEventKind::Created]);
+}
@@ -0,0 +1,264 @@
+use std::collections::{HashMap, HashSet};
+
+use infograph::types::Point;
+
+use crate::cat_error::CatError;
+use crate::cat_service::conditions::condition_path;
+use crate::cat_service::element_values::VALUE_KEY;
+use crate::cat_service::{CatService, MAIN_TABLE, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_condition::CatCondition;
+use crate::model::cat_expr::CatExpr;
+use crate::model::cat_tag_outcome::CatTagOutcome;
+use crate::model::cat_value::CatValue;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Merges the cat source into the cat target, for example to clean up duplicates, and returns
+    /// target. The points tagged with source are tagged with target, keeping the values of their
+    /// tags, unless they already are. The conditions of source are given to target and conditions
+    /// referring to source refer to target instead. Conditions of source referring to target and
+    /// conditions of target referring to source are dropped, as target would be conditioned on
+    /// itself. Then source is deleted and a link to target is recorded with it, see
+    /// get_merge_target.
+    /// The conditions as they will be after the merge are checked before the first write, so a
+    /// merge failing with CatError::Cycle, as target would depend on itself through other cats,
+    /// writes nothing. Fails with CatError::UnknownCat if source or target is not a cat currently in
+    /// the space.
+    /// The space is read once and all writes of a merge share one stamp.
+    ///
+    pub fn merge_cats(&mut self, source: Cat, target: Cat) -> Result<Cat, CatError> {
+        check_id(&source.triple_id)?;
+        check_id(&target.triple_id)?;
+        if source.triple_id == target.triple_id {
+            return Err(CatError::Cycle(vec![source.triple_id]));
+        }
+
+        let (info_triples, cats) = self.load_live(MAIN_TABLE.to_string())?;
+        for cat in [&source, &target] {
+            if cats.get_by_triple_id(cat.triple_id.clone()).is_none() {
+                return Err(CatError::UnknownCat(cat.triple_id.clone()));
+            }
+        }
+
+        // the conditions of target and those of the other cats as they will be after the merge
+        let refers_to = |condition: &CatExpr, cat: &Cat| condition.cat_ids().contains(&cat.triple_id);
+        let copied: Vec<CatExpr> = self.select_cat_condis(&info_triples, &source.triple_id)?
+            .into_iter()
+            .map(|cat_condition|cat_condition.condition)
+            .filter(|condition|!refers_to(condition, &target))
+            .collect();
+        let mut referring: Vec<String> = self.get_condition_exprs(&info_triples)
+            .into_iter()
+            .filter(|(cat_id, condition)|*cat_id != source.triple_id && refers_to(condition, &source))
+            .map(|(cat_id, _)|cat_id)
+            .collect();
+        referring.sort();
+        let mut dropped: Vec<CatCondition> = Vec::new();
+        let mut replaced: Vec<(CatCondition, CatExpr)> = Vec::new();
+        for cat_id in referring {
+            for cat_condition in self.select_cat_condis(&info_triples, &cat_id)? {
+                if !refers_to(&cat_condition.condition, &source) {
+                    continue;
+                }
+                if cat_id == target.triple_id {
+                    dropped.push(cat_condition);
+                } else {
+                    let condition = cat_condition.condition.replace_cat(&source.triple_id, &target.triple_id);
+                    replaced.push((cat_condition, condition));
+                }
+            }
+        }
+
+        let mut depends_on: HashMap<String, Vec<String>> = self.get_condition_exprs(&info_triples)
+            .into_iter()
+            .filter(|(cat_id, _)|*cat_id != source.triple_id && cats.get_by_triple_id(cat_id.clone()).is_some())
+            .map(|(cat_id, condition)|(cat_id, condition.replace_cat(&source.triple_id, &target.triple_id).cat_ids()))
+            .collect();
+        let target_depends_on: Vec<String> = self.select_cat_condis(&info_triples, &target.triple_id)?
+            .into_iter()
+            .map(|cat_condition|cat_condition.condition)
+            .filter(|condition|!refers_to(condition, &source))
+            .chain(copied.iter().cloned())
+            .flat_map(|condition|condition.cat_ids())
+            .collect();
+        depends_on.insert(target.triple_id.clone(), target_depends_on.clone());
+        for cat_id in target_depends_on.iter() {
+            if let Some(path) = condition_path(&depends_on, cat_id, &target.triple_id) {
+                return Err(CatError::Cycle(std::iter::once(target.triple_id.clone()).chain(path).collect()));
+            }
+        }
+
+        let stamp = self.create_stamp()?;
+        for condition in copied {
+            self.write_cat_condi(condition, target.triple_id.clone(), &stamp)?;
+        }
+        for (cat_condition, condition) in replaced {
+            self.write_cat_condi(condition, cat_condition.sub_cat_id.clone(), &stamp)?;
+            dropped.push(cat_condition);
+        }
+        for cat_condition in dropped {
+            let canceled = self.store.create_infotriple(cat_condition.triple_id, self.canceled_tag.clone())?;
+            self.write_stamp(canceled.id, stamp.1.clone())?;
+        }
+
+        let mut values = self.get_keyed_values(&info_triples, self.valued_tag.clone())?;
+        let (pairs, moved_values): (Vec<(Cat, Point)>, Vec<Option<CatValue>>) = self.select_cat_elements(&info_triples, source.point.clone())?
+            .into_iter()
+            .map(|cat_element|{
+                let value = values.remove(&cat_element.element_id).and_then(|mut values|values.remove(VALUE_KEY));
+                ((target.clone(), cat_element.point), value)
+            })
+            .unzip();
+        let outcomes = self.write_tags(&info_triples, &cats, pairs, &mut Some(stamp.clone()))?;
+        for (outcome, value) in outcomes.into_iter().zip(moved_values) {
+            match (outcome, value) {
+                (CatTagOutcome::Failed(error), _) => return Err(error),
+                (CatTagOutcome::Written(moved), Some(value)) => self.set_keyed_value(moved.element_id, self.valued_tag.clone(), VALUE_KEY, &value)?,
+                _ => {},
+            }
+        }
+
+        let canceled = self.store.create_infotriple(source.triple_id.clone(), self.canceled_tag.clone())?;
+        self.write_stamp(canceled.id, stamp.1.clone())?;
+        let merged = self.store.create_infotriple(source.triple_id, self.merged_tag.clone())?;
+        let link = self.store.create_infotriple(merged.id, target.triple_id.clone())?;
+        self.write_stamp(link.id, stamp.1)?;
+        Ok(target)
+    }
+
+    ///
+    /// Returns the cat the cat with the triple ID cat_id has been merged into, following the merges
+    /// of that cat in turn, so references to merged cats can be redirected. None if cat_id has not
+    /// been merged or the cat merged into no longer exists.
+    ///
+    pub fn get_merge_target(&mut self, graph_name: String, cat_id: String) -> Result<Option<Cat>, CatError> {
+
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let mut merges = self.get_decoration_values(&info_triples, self.merged_tag.clone());
+
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut current = cat_id;
+        while let Some(target_id) = merges.remove(&current).and_then(|mut targets|targets.pop()) {
+            if !seen.insert(target_id.clone()) {
+                break;
+            }
+            current = target_id;
+        }
+        Ok(match seen.is_empty() {
+            true => None,
+            false => cats.get_by_triple_id(current),
+        })
+    }
+}
+
+#[test]
+fn merge_cats_test() {
+    use crate::cat_service::test_tools::*;
+    use crate::model::cat_expr::CatExpr;
+    use crate::model::cat_value::CatValue;
+
+    let mut cat_service = scratch_service();
+    let lower = cat_service.create_cat("a", "bug", "", "").unwrap();
+    let upper = cat_service.create_cat("b", "Bug", "", "").unwrap();
+    let work = cat_service.create_cat("c", "Work", "", "").unwrap();
+    let urgent = cat_service.create_cat("d", "Urgent", "", "").unwrap();
+    cat_service.create_cat_element_w_value(lower.clone(), "note1".to_string(), CatValue::Integer(2)).unwrap();
+    cat_service.create_cat_element(lower.clone(), "note2".to_string()).unwrap();
+    cat_service.create_cat_element(upper.clone(), "note2".to_string()).unwrap();
+    cat_service.create_cat_element(upper.clone(), "note3".to_string()).unwrap();
+    cat_service.create_cat_condi(work.triple_id.clone(), lower.triple_id.clone()).unwrap();
+    cat_service.create_cat_condi_expr(CatExpr::Or(vec![on(&lower), on(&work)]), urgent.triple_id.clone()).unwrap();
+    cat_service.create_cat_condi_expr(CatExpr::Not(Box::new(on(&lower))), upper.triple_id.clone()).unwrap();
+
+    assert_eq!(cat_service.merge_cats(lower.clone(), lower.clone()).unwrap_err(), CatError::Cycle(vec![lower.triple_id.clone()]));
+    assert_eq!(cat_service.merge_cats(lower.clone(), upper.clone()).unwrap(), upper);
+
+    let elements = cat_service.get_cat_elements_w_values(graph(), "b".to_string(), None, None, false).unwrap();
+    assert_eq!(elements.clone().into_iter().next().unwrap().value, Some(CatValue::Integer(2)));
+    assert_eq!(element_points(elements), vec!["note1", "note2", "note3"]);
+    // the moves are stamped like the deletion of lower
+    let deleted = cat_service.get_cancellation_stamp(graph(), lower.triple_id.clone()).unwrap();
+    let note1 = cat_service.get_cat_elements_w_stamps(graph(), "b".to_string(), None).unwrap().into_iter().find(|ce|ce.point == "note1").unwrap();
+    assert!(deleted.is_some());
+    assert_eq!(note1.stamp, deleted);
+
+    let cats = cat_service.get_all_cats_w_conditions(graph()).unwrap();
+    assert!(cats.get("a".to_string()).is_none());
+    assert_eq!(cats.get("b".to_string()).unwrap().condition, Some(on(&work)));
+    assert_eq!(cats.get("d".to_string()).unwrap().condition, Some(CatExpr::Or(vec![on(&upper), on(&work)])));
+    assert!(cat_service.validate_conditions(graph()).unwrap().is_empty());
+
+    assert_eq!(cat_service.get_merge_target(graph(), lower.triple_id.clone()).unwrap(), cats.get("b".to_string()));
+    assert_eq!(cat_service.get_merge_target(graph(), upper.triple_id.clone()).unwrap(), None);
+    let history = cat_service.get_cat_history(graph(), &lower).unwrap();
+    assert_eq!(history.last().unwrap().kind, crate::model::cat_event::CatEventKind::Merged(upper.triple_id.clone()));
+    // merges are followed
+    cat_service.merge_cats(upper.clone(), work.clone()).unwrap();
+    assert_eq!(cat_service.get_merge_target(graph(), lower.triple_id.clone()).unwrap().unwrap().name, "Work");
+    assert_eq!(cat_service.get_cat_elements(graph(), "c".to_string()).unwrap().into_iter().count(), 3);
+    assert_eq!(cat_service.merge_cats(lower.clone(), work).unwrap_err(), CatError::UnknownCat(lower.triple_id));
+}
+
+#[test]
+fn merge_cats_cycle_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let lower = cat_service.create_cat("a", "bug", "", "").unwrap();
+    let upper = cat_service.create_cat("b", "Bug", "", "").unwrap();
+    let work = cat_service.create_cat("c", "Work", "", "").unwrap();
+    let note = cat_service.create_cat("d", "Note", "", "").unwrap();
+    cat_service.create_cat_element(lower.clone(), "note1".to_string()).unwrap();
+    cat_service.create_cat_condi(work.triple_id.clone(), lower.triple_id.clone()).unwrap();
+    cat_service.create_cat_condi(upper.triple_id.clone(), work.triple_id.clone()).unwrap();
+    cat_service.create_cat_condi(lower.triple_id.clone(), note.triple_id.clone()).unwrap();
+    let written = |cat_service: &mut crate::cat_service_mem::CatServiceMem| cat_service.store
+        .get_all_info_triples_from_info_table(graph())
+        .unwrap()
+        .len();
+    let before = written(&mut cat_service);
+
+    // upper would be conditioned on work, which is conditioned on upper
+    assert_eq!(cat_service.merge_cats(lower.clone(), upper.clone()).unwrap_err(),
+        CatError::Cycle(vec![upper.triple_id.clone(), work.triple_id.clone(), upper.triple_id.clone()]));
+    assert_eq!(written(&mut cat_service), before);
+    assert_eq!(cat_names(cat_service.get_all_cats(graph()).unwrap()), vec!["bug", "Bug", "Note", "Work"]);
+    assert_eq!(element_points(cat_service.get_cat_elements(graph(), "a".to_string()).unwrap()), vec!["note1"]);
+    // the other way round work would be conditioned on lower, which is conditioned on work
+    assert_eq!(cat_service.merge_cats(upper.clone(), lower.clone()).unwrap_err(),
+        CatError::Cycle(vec![lower.triple_id.clone(), work.triple_id.clone(), lower.triple_id.clone()]));
+    assert_eq!(written(&mut cat_service), before);
+
+    cat_service.clear_cat_condis(work.triple_id.clone()).unwrap();
+    cat_service.merge_cats(lower.clone(), upper.clone()).unwrap();
+    let cats = cat_service.get_all_cats_w_conditions(graph()).unwrap();
+    assert_eq!(cats.get("b".to_string()).unwrap().condition, Some(on(&work)));
+    assert_eq!(cats.get("d".to_string()).unwrap().condition, Some(on(&upper)));
+    assert!(cat_service.validate_conditions(graph()).unwrap().is_empty());
+}
+
+#[test]
+fn merge_cats_fs_test() {
+    use crate::cat_service::test_tools::*;
+    use crate::model::cat_value::CatValue;
+
+    let (mut cat_service, _guard) = scratch_service_fs("merge_cats_fs_test");
+    let lower = cat_service.create_cat("merge_fs_a", "bug", "", "").unwrap();
+    let upper = cat_service.create_cat("merge_fs_b", "Bug", "", "").unwrap();
+    let work = cat_service.create_cat("merge_fs_c", "Work", "", "").unwrap();
+    cat_service.create_cat_element_w_value(lower.clone(), "merge_fs_note1".to_string(), CatValue::Integer(2)).unwrap();
+    cat_service.create_cat_element(upper.clone(), "merge_fs_note2".to_string()).unwrap();
+    cat_service.create_cat_condi(lower.triple_id.clone(), work.triple_id.clone()).unwrap();
+
+    cat_service.merge_cats(lower.clone(), upper.clone()).unwrap();
+    let elements = cat_service.get_cat_elements_w_values(graph(), "merge_fs_b".to_string(), None, None, false).unwrap();
+    assert_eq!(elements.clone().into_iter().next().unwrap().value, Some(CatValue::Integer(2)));
+    assert_eq!(element_points(elements), vec!["merge_fs_note1", "merge_fs_note2"]);
+    let cats = cat_service.get_all_cats_w_conditions(graph()).unwrap();
+    assert_eq!(cat_names(cats.clone()), vec!["Bug", "Work"]);
+    assert_eq!(cats.get("merge_fs_c".to_string()).unwrap().condition, Some(on(&upper)));
+    assert_eq!(cat_service.get_merge_target(graph(), lower.triple_id).unwrap().unwrap().name, "Bug");
+}
@@ -0,0 +1,573 @@
+
+use std::collections::{HashMap, HashSet};
+
+use infograph::types::{NameOrPoint, Point};
+use infograph::{InfoTable, InfoTriple, Descriptor};
+
+use crate::cat_error::CatError;
+use crate::model::cat::Cat;
+use crate::model::cat_condition::CatCondition;
+use crate::model::cat_element::CatElement;
+use crate::model::cat_element_list::CatElementList;
+use crate::model::cat_expr::CatExpr;
+use crate::model::cat_list::CatList;
+use crate::model::cat_stamp::CatStamp;
+use crate::store::cat_store::CatStore;
+
+mod bulk_tags;
+mod conditions;
+mod element_values;
+mod facets;
+mod hierarchy;
+mod merges;
+mod history;
+mod ordinals;
+mod point_cats;
+mod positions;
+mod properties;
+mod queries;
+mod revisions;
+mod splits;
+mod stamps;
+#[cfg(test)]
+mod test_tools;
+mod visibility;
+
+///
+/// The info table new info triples are appended to. Writes that need to check the current state
+/// of the space read it from here.
+///
+const MAIN_TABLE: &str = "main_table";
+
+///
+/// The category logic of a space written once against the CatStore trait.
+/// See CatServiceFS for the filesystem backed version.
+///
+#[derive(Clone)]
+pub struct CatService<S: CatStore> {
+    cat_tag: String,
+    canceled_tag: String,
+    conditioned_tag: String,
+    revised_tag: String,
+    parent_tag: String,
+    and_tag: String,
+    or_tag: String,
+    not_tag: String,
+    facet_tag: String,
+    ordinal_tag: String,
+    position_tag: String,
+    property_tag: String,
+    valued_tag: String,
+    stamped_tag: String,
+    merged_tag: String,
+    actor: String,
+    pub space_id: String,
+    store: S,
+}
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Creates a CatService for the space space_id working on the storage given as store.
+    ///
+    pub fn from_store(space_id: String, store: S) -> Self {
+
+        CatService {
+            space_id,
+            cat_tag: "5fe9374c5e9e27ebb78f8bf7cd78bbb23ee51e672dc54c603ec1c5b3eef33feb".to_string(),
+            canceled_tag: "1c1ca1cef969f76f757fdf7ff3ff9f365a864ef579f2d45866f8d0d5ef9f01df".to_string(),
+            conditioned_tag: "9b2478b1a6e89633eede884aa57fd1a1fb2e4ed279b58e9d1cd15010a103c5bc".to_string(), 
+            revised_tag: "1e4de31f94535968f4e4ca8fd577a4598e984bc5c89dae095924e944c121c7a3".to_string(),
+            parent_tag: "e47125968b3b71049fbc4802d1e40a71ea1359decfabacf70b34588037d4ff0c".to_string(),
+            and_tag: "fdb8ea768527acad5708c8153bf45d07e4157931cd3bb58cef81f265123709cf".to_string(),
+            or_tag: "ea8ae6438ebe942eea00b44b7e6e2a4021202e544b4931ea37e0af9472fda256".to_string(),
+            not_tag: "ecb85647cab2771273ca329cf9cfcdb1f76221d4ecb106dea527156a0ce4d70a".to_string(),
+            facet_tag: "5c07ce13958f56fdb7c9c5436785723f50e63294db365bec4cd25a89d7d9fb19".to_string(),
+            ordinal_tag: "8a4ba3186720a20e211590558cfe765c9afdcc6e30b0909de2faec888bf8d406".to_string(),
+            position_tag: "5a452459503be70d2ed2fca46dd91e05e820a957f175686a77d03fff80f664fe".to_string(),
+            property_tag: "fc9184134ae5672878413fff088dd0a1db30d18dc2a855da6345c7ab592ac46f".to_string(),
+            valued_tag: "614b732cdd729bdbb84c3112f2934e2930e084c3b95a5dca5e96225a3628fcf1".to_string(),
+            stamped_tag: "ca2fe80680584cc13ea8288c0ca1db7159e62f523c73775ef7ceecd062bf15a1".to_string(),
+            merged_tag: "3f8f09c8e09f712b362183db69f4f061bd948d7a61e7663b585d723602c559b1".to_string(),
+            actor: "".to_string(),
+            store,
+        }
+    }
+
+    ///
+    /// Tells whether id is one of the tags the service decorates triples and points with.
+    ///
+    pub(super) fn is_tag(&self, id: &str) -> bool {
+        [&self.cat_tag, &self.canceled_tag, &self.conditioned_tag, &self.revised_tag, &self.parent_tag,
+         &self.and_tag, &self.or_tag, &self.not_tag, &self.facet_tag, &self.ordinal_tag, &self.position_tag,
+         &self.property_tag, &self.valued_tag, &self.stamped_tag, &self.merged_tag]
+            .into_iter()
+            .any(|tag|tag == id)
+    }
+
+    ///
+    /// Turns the info triples of an info table into an InfoTable of the triples currently in effect.
+    /// See live_info_triples.
+    ///
+    pub fn live_info_table(&self, info_triples: Vec<InfoTriple>) -> InfoTable {
+        InfoTable::from_iter(self.live_info_triples(info_triples))
+    }
+
+    ///
+    /// Filters the info triples of an info table, in the order they were appended, down to the
+    /// triples currently in effect while keeping the order.
+    /// Triple IDs are derived from the paired IDs, so creating a triple again appends the same triple
+    /// once more. A triple is therefore canceled only when its canceled decoration was appended after
+    /// the last time the triple itself was appended, and a canceled decoration older than its triple
+    /// is left out as it no longer has any effect.
+    ///
+    pub fn live_info_triples(&self, info_triples: Vec<InfoTriple>) -> Vec<InfoTriple> {
+
+        let mut last_appended: HashMap<String, usize> = HashMap::new();
+        info_triples
+            .iter()
+            .enumerate()
+            .for_each(|(i, it)|{
+                last_appended.insert(it.id.clone(), i);
+            });
+
+        let is_canceled = |id: &String, canceled_id: &String| -> bool {
+            match (last_appended.get(id), last_appended.get(canceled_id)) {
+                (Some(appended), Some(canceled)) => canceled > appended,
+                _ => false,
+            }
+        };
+
+        let canceled_ids: HashMap<String, String> = info_triples
+            .iter()
+            .filter_map(|it|{
+                it.other_half(self.canceled_tag.clone())
+                    .ok()
+                    .map(|target|(target, it.id.clone()))
+            })
+            .collect();
+
+        info_triples
+            .iter()
+            .filter(|it|{
+                match it.other_half(self.canceled_tag.clone()) {
+                    Ok(target) => is_canceled(&target, &it.id) || !last_appended.contains_key(&target),
+                    Err(..) => !canceled_ids
+                        .get(&it.id)
+                        .is_some_and(|canceled_id|is_canceled(&it.id, canceled_id)),
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
+    ///
+    /// Returns for each ID decorated with tag the IDs paired with that decoration, in the order
+    /// they were appended. An ID appended more than once is listed once for each time.
+    /// This is how values are attached to a triple, like the super cats of a conditioned cat or the
+    /// revisions of a cat.
+    ///
+    pub fn get_decoration_values(&self, info_triples: &[InfoTriple], tag: String) -> HashMap<String, Vec<String>> {
+
+        let decorations: HashMap<String, String> = info_triples
+            .iter()
+            .filter_map(|it|{
+                it.other_half(tag.clone())
+                    .ok()
+                    .map(|decorated|(it.id.clone(), decorated))
+            })
+            .collect();
+
+        let mut result: HashMap<String, Vec<String>> = HashMap::new();
+        info_triples
+            .iter()
+            .for_each(|it|{
+                [(&it.id1, &it.id2), (&it.id2, &it.id1)]
+                    .iter()
+                    .filter_map(|(decoration_id, value)|{
+                        decorations.get(*decoration_id).map(|decorated|(decorated, value))
+                    })
+                    .for_each(|(decorated, value)|{
+                        result.entry(decorated.clone()).or_default().push(value.to_string());
+                    });
+            });
+
+        result
+    }
+
+    ///
+    /// Loads and returns a cat_list object for data storage specified in the parameter graph_name.
+    ///
+    pub fn get_all_cats(&mut self, graph_name: String) -> Result<CatList, CatError> {
+//println!("Cat::get_all_cats was called!!! for graph_name {}", graph_name);
+
+        Ok(self.load_live(graph_name)?.1)
+        
+    }
+
+    ///
+    /// Loads the info triples of graph_name currently in effect, in the order they were appended,
+    /// together with the cats they describe.
+    ///
+    fn load_live(&mut self, graph_name: String) -> Result<(Vec<InfoTriple>, CatList), CatError> {
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        self.load_live_from(info_triples)
+    }
+
+    ///
+    /// Like load_live, but for the info triples of an info table given as info_triples.
+    ///
+    fn load_live_from(&mut self, info_triples: Vec<InfoTriple>) -> Result<(Vec<InfoTriple>, CatList), CatError> {
+
+        let info_triples = self.live_info_triples(info_triples);
+        let mut info_table = InfoTable::from_iter(info_triples.clone());    
+
+        let cat_table = info_table.get_neighbors_except_decorated(self.cat_tag.clone(), self.canceled_tag.clone());
+        let cats = self.load_cats(cat_table, &info_triples)?;
+
+        Ok((info_triples, cats))
+    }
+
+    ///
+    /// Returns a CatList for the cat triples in cat_table with their descriptor notes loaded in one
+    /// go. If a cat has been updated the latest revision found in info_triples is used. The cats'
+    /// positions are set from the display order stored last in info_triples.
+    ///
+    fn load_cats(&mut self, cat_table: InfoTable, info_triples: &[InfoTriple]) -> Result<CatList, CatError> {
+
+        let mut cat_list = cat_table.get_neighbor_ids(self.cat_tag.clone());
+        let revisions = self.get_latest_revisions(info_triples);
+        cat_list.extend(revisions.values().cloned());
+
+        let descs = self.store.get_descs_hashmap_for_list(cat_list)?;
+
+        let cats = self.populate_cat_list(cat_table, descs.clone())?;
+        let cats = self.apply_revisions(cats, &revisions, &descs)?;
+        Ok(self.apply_positions(cats, info_triples))
+    }
+    
+    ///
+    /// Loads and returns a cat_list object for data storage specified in the parameter graph_name.
+    /// Populate the cats with the condition expression of each cat if any.
+    ///
+    pub fn get_all_cats_w_conditions(&mut self, graph_name: String) -> Result<CatList, CatError> {
+//println!("Cat::get_all_cats_w_conditions was called!!! for graph_name {}", graph_name);
+
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let mut conditions = self.get_condition_exprs(&info_triples);
+
+        let mut cat_list = CatList::new();
+
+        cats.into_iter()
+            .map(|mut cat_w_condi|{
+                cat_w_condi.condition = conditions.remove(&cat_w_condi.triple_id);
+//println!("cat_w_condi:{:?}",cat_w_condi);
+                cat_w_condi
+            })
+            .for_each(|cat_w_condi|{
+                cat_list.add(cat_w_condi);
+            });
+
+        Ok(cat_list)
+    }
+
+
+/// Returning a list of cat_ids (list can be empty) that a cat_id is conditioned by.   
+#[deprecated(note = "use get_all_cats_w_conditions and Cat::condition instead")]
+#[allow(deprecated)]
+pub fn get_condition_list(&self, cat_triple_id: String, info_table: InfoTable, conditioned_table: InfoTable) -> Vec<String> {
+//println!("Cat::get_condition_list was called!!! for cat_triple_id {}", cat_triple_id);
+        
+    let conditioned_id = self.get_conditioned_decoration_id(cat_triple_id, conditioned_table);
+
+//println!("conditioned_id: {:?}", conditioned_id);
+
+    match conditioned_id {
+        Some(conditioned_id) => info_table.get_neighbor_ids(conditioned_id),
+//println!("No thing");        
+        None => Vec::new(),
+    }
+}
+
+/// Helper method that finds the triple_id (if any) used for adding conditions to a certain cat_id
+#[deprecated(note = "use get_all_cats_w_conditions and Cat::condition instead")]
+pub fn get_conditioned_decoration_id(&self, cat_triple_id: String, conditioned_table: InfoTable) -> Option<String>{
+
+    conditioned_table
+        .get_neighbor_triple_ids_only(cat_triple_id)
+        .pop()
+}
+
+    ///
+    /// Returns a CatList with a Cat for each cat triple in info_table, enriched with the descriptor
+    /// notes in descs which are expected to be keyed by point.
+    /// Fails if a triple is not paired with the cat tag or if a cat has no descriptor note.
+    ///
+    pub fn populate_cat_list(&self, mut info_table: InfoTable, descs: HashMap<String, Descriptor>) -> Result<CatList, CatError> {
+
+//println!("Cat::populate_cat_list was called!!!");
+
+        let mut result = CatList::new();
+        for it in info_table.get_info_triples().iter() {
+                let point = &it.other_half(self.cat_tag.clone())
+                    .map_err(CatError::MalformedTriple)?;
+                let desc = descs.get(point)
+                    .ok_or_else(|| CatError::MissingDescriptor(point.to_string()))?;
+                result.add(Cat{
+                    triple_id: it.id.clone(),
+                    point: point.to_string(),
+                    type_id: self.cat_tag.clone(),
+                    desc_id: desc.desc_id.clone(),
+                    name: desc.name.clone(),
+                    icon_uri: desc.label.clone(),
+                    description: desc.description.clone(),
+                    condition: None,
+                    position: None,
+                    properties: HashMap::new(),
+                    stamp: None,
+                });
+        }
+
+        Ok(result)
+        
+    }
+
+    ///
+    /// Loads and returns the elements tagged with the cat cat_id, populated with the descriptor
+    /// notes of the tagged points. The descriptor notes are loaded in one go.
+    /// Fails with CatError::UnknownCat if cat_id has never been created as a cat.
+    ///
+    pub fn get_cat_elements(&mut self, graph_name: String, cat_id: String) -> Result<CatElementList, CatError> {
+//println!("Cat::get_cat_elements was called!!! for cat_id {}", cat_id);
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name.clone())?;
+        let info_triples = self.live_info_triples(info_triples);
+
+        self.select_cat_elements(&info_triples, cat_id)
+    }
+
+    ///
+    /// Returns the elements tagged with the cat cat_id according to the live info triples
+    /// info_triples. Fails with CatError::UnknownCat if cat_id is not a cat in there.
+    ///
+    fn select_cat_elements(&self, info_triples: &[InfoTriple], cat_id: String) -> Result<CatElementList, CatError> {
+
+        let mut info_table = InfoTable::from_iter(info_triples.to_vec());    
+
+        if !info_table.get_neighbor_ids(self.cat_tag.clone()).contains(&cat_id) {
+            return Err(CatError::UnknownCat(cat_id));
+        }
+        
+        let element_table = info_table.get_neighbors_except_decorated_and_not(cat_id.clone(), self.canceled_tag.clone(), self.cat_tag.clone());
+
+        self.populate_cat_element_list(cat_id, element_table)
+    }
+
+    ///
+    /// Returns a CatElementList with a CatElement for each triple in element_table pairing cat_id
+    /// with a tagged point. The descriptor notes of the tagged points are loaded in one go.
+    ///
+    fn populate_cat_element_list(&self, cat_id: String, element_table: InfoTable) -> Result<CatElementList, CatError> {
+
+        let element_points = element_table.get_neighbor_ids(cat_id.clone());
+        let mut descs = self.store.get_descs_hashmap_for_list(element_points)?;
+
+        let mut result = CatElementList::new();
+        element_table
+            .into_iter()
+            .for_each(|it|{
+//println!("cat_element found: {:?}",it);                
+                if let Ok(mut ce) = CatElement::from_triple(cat_id.clone(), it) {
+                    if let Some(desc) = descs.remove(&ce.point) {
+                        ce.add_desc(desc);
+                    }
+                    result.add(ce);
+                }
+            });
+
+        Ok(result)
+    }
+
+    ///
+    /// Returns the names of the elements tagged with the cat cat_id keyed by point. Elements without
+    /// a name are given their point as name.
+    ///
+    #[deprecated(note = "use get_cat_elements and CatElementList::get_all_cat_element_names instead")]
+    pub fn get_all_cat_elements(&mut self, graph_name: String, cat_id: String) -> Result<HashMap<Point, NameOrPoint>, CatError> {
+//println!("Cat::get_all_cat_elements was called!!! for cat_id {}", cat_id);
+
+        Ok(self.get_cat_elements(graph_name, cat_id)?
+            .get_all_cat_element_names())
+    }
+
+   ///
+   /// Creates a cat from a descriptor note and a cat triple and returns it.
+   /// Fails with CatError::InvalidValue if cat_point is a cat already, deleted ones included, as
+   /// creating it again would restore the deleted cat with all it had. See restore_cat.
+   ///
+   pub fn create_cat(&mut self, cat_point: &str, cat_name: &str, cat_icon: &str, cat_desc: &str) -> Result<Cat, CatError> {
+        check_id(cat_point)?;
+        let info_triples = self.store.get_all_info_triples_from_info_table(MAIN_TABLE.to_string())?;
+        if self.get_all_cat_points(&info_triples).contains(cat_point) {
+            return Err(CatError::InvalidValue(format!("{} is a cat already", cat_point)));
+        }
+    
+        let stamp = self.create_stamp()?;
+        self.write_cat(cat_point, cat_name, cat_icon, cat_desc, &stamp)
+   }
+
+    ///
+    /// Writes the descriptor note and the cat triple of a cat, stamped with stamp as returned by
+    /// create_stamp, and returns the cat. Nothing is checked, see create_cat for the checks.
+    ///
+    pub(super) fn write_cat(&mut self, cat_point: &str, cat_name: &str, cat_icon: &str, cat_desc: &str, stamp: &(CatStamp, String)) -> Result<Cat, CatError> {
+        let desc = self.store.create_desc(cat_point.to_string(),
+                                          cat_name.to_string(), cat_icon.to_string(), cat_desc.to_string())?;
+        let it = self.store.create_infotriple(cat_point.to_string(), self.cat_tag.to_string())?;
+        self.write_stamp(it.id.clone(), stamp.1.clone())?;
+
+        Ok(Cat {
+            triple_id: it.id,
+            point: desc.point,
+            type_id: self.cat_tag.clone(),
+            desc_id: desc.desc_id,
+            name: desc.name,
+            icon_uri: desc.label,
+            description: desc.description,
+            condition: None,
+            position: None,
+            properties: HashMap::new(),
+            stamp: Some(stamp.0.clone()),
+        })
+    }
+
+   ///
+   /// Cancels the cat and returns it.
+   ///
+   pub fn delete_cat(&mut self, cat: Cat) -> Result<Cat, CatError> {
+        if cat.triple_id.is_empty() {
+            return Err(CatError::UnknownCat(cat.point));
+        }
+        let id1 = cat.triple_id.to_string(); 
+
+        self.cancel_infotriple(id1)?;
+        Ok(cat)
+   }
+
+    ///
+    /// Returns the points of all cats created in info_triples, all triples of an info table,
+    /// including the cats deleted since.
+    ///
+    fn get_all_cat_points(&self, info_triples: &[InfoTriple]) -> HashSet<Point> {
+        info_triples
+            .iter()
+            .filter_map(|it|it.other_half(self.cat_tag.clone()).ok())
+            .collect()
+    }
+
+    ///
+    /// Loads and returns the cats that have been deleted (canceled) and not restored since, populated
+    /// with their descriptor notes.
+    ///
+    pub fn get_canceled_cats(&mut self, graph_name: String) -> Result<CatList, CatError> {
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let live_triples = self.live_info_triples(info_triples.clone());
+        let live_table = InfoTable::from_iter(live_triples.clone());
+
+        let cat_table: InfoTable = InfoTable::from_iter(info_triples)
+            .get_neighbors_as_triples(self.cat_tag.clone())
+            .into_iter()
+            .filter(|it|!live_table.rows.contains_key(&it.id))
+            .collect();
+
+        self.load_cats(cat_table, &live_triples)
+    }
+
+    ///
+    /// Restores a deleted cat by appending its cat triple once more, so the cancellation stays in
+    /// the info table as history. Tagged elements and conditions of the cat are kept.
+    /// Returns the cat with the stamp of the restore.
+    /// Fails with CatError::UnknownCat if cat.point was never a cat and with
+    /// CatError::InvalidValue if the cat has not been deleted.
+    ///
+    pub fn restore_cat(&mut self, cat: Cat) -> Result<Cat, CatError> {
+        check_id(&cat.point)?;
+        let info_triples = self.store.get_all_info_triples_from_info_table(MAIN_TABLE.to_string())?;
+        if !self.get_all_cat_points(&info_triples).contains(&cat.point) {
+            return Err(CatError::UnknownCat(cat.point));
+        }
+        if self.get_all_cat_points(&self.live_info_triples(info_triples)).contains(&cat.point) {
+            return Err(CatError::InvalidValue(format!("{} has not been deleted", cat.point)));
+        }
+        let (_, stamp) = self.create_stamped_infotriple(cat.point.clone(), self.cat_tag.clone())?;
+
+        let mut result = cat;
+        result.stamp = Some(stamp);
+        Ok(result)
+    }
+
+    ///
+    /// Tags id_to_tag with the cat and returns the resulting CatElement including the descriptor
+    /// note of id_to_tag if it has one.
+    /// If the cat is a member of a facet the tags of id_to_tag with the other members of the facet
+    /// are removed.
+    ///
+    pub fn create_cat_element(&mut self, cat: Cat, id_to_tag: String) -> Result<CatElement, CatError> {
+        if cat.point.is_empty() {
+            return Err(CatError::UnknownCat(cat.triple_id));
+        }
+        check_id(&id_to_tag)?;
+        let (it, stamp) = self.create_stamped_infotriple(cat.point.clone(), id_to_tag.clone())?;
+        self.cancel_facet_siblings(&cat, &id_to_tag)?;
+
+        let mut cat_element = CatElement::from_triple(cat.point, it)?;
+        cat_element.stamp = Some(stamp);
+        if let Some(desc) = self.store.get_descs_hashmap_for_list(vec![id_to_tag.clone()])?.remove(&id_to_tag) {
+            cat_element.add_desc(desc);
+        }
+        Ok(cat_element)
+    } 
+    
+    ///
+    /// Removes the tag cat_element represents by canceling its triple and returns the removed
+    /// CatElement. The point can be tagged with the cat again later on.
+    ///
+    pub fn remove_cat_element(&mut self, cat_element: &CatElement) -> Result<CatElement, CatError> {
+        check_id(&cat_element.element_id)?;
+        self.cancel_infotriple(cat_element.element_id.clone())?;
+        Ok(cat_element.clone())
+    }
+
+    ///
+    /// Like remove_cat_element but for a whole list of cat elements.
+    /// Stops at the first failing cat element and returns the error.
+    ///
+    pub fn remove_cat_elements(&mut self, cat_elements: CatElementList) -> Result<CatElementList, CatError> {
+        let mut result = CatElementList::new();
+        for cat_element in cat_elements {
+            result.add(self.remove_cat_element(&cat_element)?);
+        }
+        Ok(result)
+    }
+
+    ///
+    /// Makes the cat with the triple ID sub_cat_id conditioned by the cat super_cat_id and returns
+    /// the resulting CatCondition.
+    /// Same as create_cat_condi_expr with CatExpr::Cat(super_cat_id).
+    ///
+    pub fn create_cat_condi(&mut self, super_cat_id: String, sub_cat_id: String) -> Result<CatCondition, CatError> {
+        self.create_cat_condi_expr(CatExpr::Cat(super_cat_id), sub_cat_id)
+    }
+}
+
+///
+/// Info triples are stored as space separated lines, so an id used in a triple can neither be empty
+/// nor contain whitespace.
+///
+fn check_id(id: &str) -> Result<(), CatError> {
+    if id.is_empty() || id.contains(char::is_whitespace) {
+        return Err(CatError::MalformedTriple(format!("\"{}\" cannot be used as an id in an info triple", id)));
+    }
+    Ok(())
+}
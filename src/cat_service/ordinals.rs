@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use infograph::framework::ig_tools::hashing_tools;
+use infograph::InfoTriple;
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_element::CatElement;
+use crate::model::cat_element_list::CatElementList;
+use crate::model::cat_facet::CatFacet;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Declares the order of the member cats of facet, lowest first, which makes the facet ordinal,
+    /// and returns the updated facet. Members left out of cats keep their place after the ordered
+    /// ones. Fails with CatError::UnknownCat if a cat is not a member of facet.
+    /// The order is stored as a list node whose ID is derived from the cats in it, paired with the
+    /// ordinal decoration of the facet's triple, so the latest order declared wins.
+    ///
+    pub fn set_cat_facet_order(&mut self, facet: CatFacet, cats: Vec<Cat>) -> Result<CatFacet, CatError> {
+        check_id(&facet.triple_id)?;
+        for cat in cats.iter() {
+            if !facet.cat_ids.contains(&cat.triple_id) {
+                return Err(CatError::UnknownCat(cat.triple_id.clone()));
+            }
+        }
+        let mut order: Vec<String> = Vec::new();
+        cats.into_iter().for_each(|cat|{
+            if !order.contains(&cat.triple_id) {
+                order.push(cat.triple_id);
+            }
+        });
+
+        let list_id = hashing_tools::hash_text(&format!("{} {}", self.ordinal_tag, order.join(" ")));
+        let list = self.store.create_infotriple(list_id.clone(), self.ordinal_tag.clone())?;
+        for cat_id in order.iter() {
+            self.store.create_infotriple(list.id.clone(), cat_id.clone())?;
+        }
+        let ordinal = self.store.create_infotriple(facet.triple_id.clone(), self.ordinal_tag.clone())?;
+        self.store.create_infotriple(ordinal.id, list_id)?;
+
+        let mut result = facet;
+        result.cat_ids.retain(|cat_id|!order.contains(cat_id));
+        order.append(&mut result.cat_ids);
+        result.cat_ids = order;
+        result.ordinal = true;
+        Ok(result)
+    }
+
+    ///
+    /// Loads and returns the elements whose current value in facet lies between min and max, both
+    /// included, sorted by that value and then by point. A bound of None leaves that side open.
+    /// The order is the one of facet.cat_ids. Fails with CatError::UnknownCat if a bound is not a
+    /// member of facet.
+    ///
+    pub fn get_cat_elements_in_range(&mut self, graph_name: String, facet: &CatFacet, min: Option<Cat>, max: Option<Cat>) -> Result<CatElementList, CatError> {
+
+        let position = |bound: Option<Cat>, open: usize| -> Result<usize, CatError> {
+            match bound {
+                None => Ok(open),
+                Some(cat) => facet.cat_ids
+                    .iter()
+                    .position(|cat_id|*cat_id == cat.triple_id)
+                    .ok_or(CatError::UnknownCat(cat.triple_id)),
+            }
+        };
+        let range = position(min, 0)?..=position(max, facet.cat_ids.len())?;
+        let in_range: Vec<&String> = facet.cat_ids
+            .iter()
+            .enumerate()
+            .filter(|(i, _)|range.contains(i))
+            .map(|(_, cat_id)|cat_id)
+            .collect();
+
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let tags: Vec<(InfoTriple, Cat)> = self.get_facet_tags(&info_triples, &cats, facet)
+            .into_values()
+            .filter(|(_, cat)|in_range.contains(&&cat.triple_id))
+            .collect();
+
+        let mut result = CatElementList::new();
+        let mut descs = self.store.get_descs_hashmap_for_list(tags
+            .iter()
+            .filter_map(|(it, cat)|it.other_half(cat.point.clone()).ok())
+            .collect())?;
+        for (it, cat) in tags {
+            let mut cat_element = CatElement::from_triple(cat.point, it)?;
+            if let Some(desc) = descs.remove(&cat_element.point) {
+                cat_element.add_desc(desc);
+            }
+            result.add(cat_element);
+        }
+
+        let cat_points: Vec<String> = facet.cat_ids
+            .iter()
+            .filter_map(|cat_id|cats.get_by_triple_id(cat_id.clone()))
+            .map(|cat|cat.point)
+            .collect();
+        result.sort_by_ordinal(&cat_points);
+        Ok(result)
+    }
+
+    ///
+    /// Returns the latest order declared for each ordinal facet in info_triples keyed by the facet's
+    /// triple ID.
+    ///
+    pub(super) fn get_facet_orders(&self, info_triples: &[InfoTriple]) -> HashMap<String, Vec<String>> {
+        let mut lists = self.get_decoration_values(info_triples, self.ordinal_tag.clone());
+        let facet_ids: Vec<String> = info_triples
+            .iter()
+            .filter_map(|it|it.other_half(self.facet_tag.clone()).ok().map(|_|it.id.clone()))
+            .collect();
+
+        facet_ids
+            .into_iter()
+            .filter_map(|facet_id|{
+                let list_id = lists.get(&facet_id)?.last()?.clone();
+                let mut order: Vec<String> = Vec::new();
+                lists.remove(&list_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .for_each(|cat_id|{
+                        if !order.contains(&cat_id) {
+                            order.push(cat_id);
+                        }
+                    });
+                Some((facet_id, order))
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn ordinal_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let low = cat_service.create_cat("a", "Low", "", "").unwrap();
+    let high = cat_service.create_cat("b", "High", "", "").unwrap();
+    let medium = cat_service.create_cat("c", "Medium", "", "").unwrap();
+    cat_service.store.create_desc("task3".to_string(), "Task 3".to_string(), "".to_string(), "".to_string()).unwrap();
+
+    let priority = cat_service.create_cat_facet("priority", "Priority", "").unwrap();
+    let priority = cat_service.add_cat_to_facet(priority, high.clone()).unwrap();
+    let priority = cat_service.add_cat_to_facet(priority, low.clone()).unwrap();
+    let priority = cat_service.add_cat_to_facet(priority, medium.clone()).unwrap();
+    assert!(!priority.ordinal);
+    assert!(cat_service.set_cat_facet_order(priority.clone(), vec![Cat::new()]).is_err());
+    let priority = cat_service.set_cat_facet_order(priority, vec![low.clone(), medium.clone(), high.clone()]).unwrap();
+    assert_eq!(priority.cat_ids, vec![low.triple_id.clone(), medium.triple_id.clone(), high.triple_id.clone()]);
+    assert_eq!(cat_service.get_all_cat_facets(graph()).unwrap(), vec![priority.clone()]);
+
+    cat_service.create_cat_element(high.clone(), "task1".to_string()).unwrap();
+    cat_service.create_cat_element(low.clone(), "task2".to_string()).unwrap();
+    cat_service.create_cat_element(medium.clone(), "task3".to_string()).unwrap();
+    cat_service.create_cat_element(medium.clone(), "task4".to_string()).unwrap();
+
+    let at_least_medium = cat_service.get_cat_elements_in_range(graph(), &priority, Some(medium.clone()), None).unwrap();
+    assert_eq!(at_least_medium.clone().into_iter().next().unwrap().name, "Task 3");
+    assert_eq!(element_points(at_least_medium), vec!["task3", "task4", "task1"]);
+    assert_eq!(element_points(cat_service.get_cat_elements_in_range(graph(), &priority, None, Some(medium.clone())).unwrap()),
+        vec!["task2", "task3", "task4"]);
+
+    // declaring another order replaces the first one
+    let priority = cat_service.set_cat_facet_order(priority, vec![high.clone(), medium.clone()]).unwrap();
+    assert_eq!(priority.cat_ids, vec![high.triple_id.clone(), medium.triple_id.clone(), low.triple_id.clone()]);
+    assert_eq!(cat_service.get_all_cat_facets(graph()).unwrap(), vec![priority.clone()]);
+    assert_eq!(element_points(cat_service.get_cat_elements_in_range(graph(), &priority, Some(medium), None).unwrap()),
+        vec!["task3", "task4", "task2"]);
+}
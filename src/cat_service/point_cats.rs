@@ -0,0 +1,90 @@
+use std::collections::{HashMap, HashSet};
+
+use infograph::types::Point;
+use infograph::{InfoTable, InfoTriple};
+
+use crate::cat_error::CatError;
+use crate::cat_service::CatService;
+use crate::model::cat_list::CatList;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Returns the cats a point has been tagged with. The reverse of get_cat_elements.
+    ///
+    pub fn get_cats_for_point(&mut self, graph_name: String, point: Point) -> Result<CatList, CatError> {
+        Ok(self.get_cats_for_points(graph_name, vec![point.clone()])?
+            .remove(&point)
+            .unwrap_or_default())
+    }
+
+    ///
+    /// Like get_cats_for_point but for many points at once. The info table and the descriptor notes
+    /// are only loaded once. Every point in points gets an entry, points without cats get an empty
+    /// CatList.
+    ///
+    pub fn get_cats_for_points(&mut self, graph_name: String, points: Vec<Point>) -> Result<HashMap<Point, CatList>, CatError> {
+
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        Ok(select_cats_for_points(info_triples, &cats, points))
+    }
+}
+
+///
+/// Returns for each point in points the cats from cats it is tagged with according to info_triples.
+/// Every point gets an entry, points without cats get an empty CatList.
+///
+pub(super) fn select_cats_for_points(info_triples: Vec<InfoTriple>, cats: &CatList, points: Vec<Point>) -> HashMap<Point, CatList> {
+
+    let info_table = InfoTable::from_iter(info_triples);
+
+    let wanted: HashSet<&Point> = points.iter().collect();
+    let mut result: HashMap<Point, CatList> = points
+        .iter()
+        .map(|point|(point.clone(), CatList::new()))
+        .collect();
+
+    info_table
+        .into_iter()
+        .for_each(|it|{
+            [(&it.id1, &it.id2), (&it.id2, &it.id1)]
+                .iter()
+                .filter(|(point, _)|wanted.contains(point))
+                .for_each(|(point, cat_id)|{
+                    if let (Some(cat), Some(cat_list)) = (cats.get(cat_id.to_string()), result.get_mut(*point)) {
+                        cat_list.add(cat);
+                    }
+                });
+        });
+
+    result
+}
+
+#[test]
+fn get_cats_for_point_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let cat_a = cat_service.create_cat("a", "name1", "uri1", "cat no. 1").unwrap();
+    let cat_b = cat_service.create_cat("b", "name2", "uri2", "cat no. 2").unwrap();
+    let cat_c = cat_service.create_cat("c", "name3", "uri3", "cat no. 3").unwrap();
+    cat_service.create_cat_element(cat_a.clone(), "note1".to_string()).unwrap();
+    cat_service.create_cat_element(cat_b.clone(), "note1".to_string()).unwrap();
+    let removed = cat_service.create_cat_element(cat_c.clone(), "note1".to_string()).unwrap();
+    cat_service.remove_cat_element(&removed).unwrap();
+    cat_service.create_cat_element(cat_c.clone(), "note2".to_string()).unwrap();
+
+    let cats = cat_service.get_cats_for_point(graph(), "note1".to_string()).unwrap();
+    let points: Vec<String> = cats.into_iter().map(|cat|cat.point).collect();
+    assert_eq!(points, vec!["a", "b"]);
+
+    cat_service.delete_cat(cat_b).unwrap();
+    let result = cat_service.get_cats_for_points(graph(),
+        vec!["note1".to_string(), "note2".to_string(), "note3".to_string()]).unwrap();
+    let points: Vec<String> = result.get("note1").unwrap().clone().into_iter().map(|cat|cat.point).collect();
+    assert_eq!(points, vec!["a"]);
+    let points: Vec<String> = result.get("note2").unwrap().clone().into_iter().map(|cat|cat.point).collect();
+    assert_eq!(points, vec!["c"]);
+    assert!(result.get("note3").unwrap().is_empty());
+}
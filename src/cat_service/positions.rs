@@ -0,0 +1,151 @@
+use std::collections::{HashMap, HashSet};
+
+use infograph::framework::ig_tools::hashing_tools;
+use infograph::InfoTriple;
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, MAIN_TABLE};
+use crate::model::cat::Cat;
+use crate::model::cat_list::CatList;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Moves cat right in front of the cat other in the display order and returns the cats in their
+    /// new order.
+    ///
+    pub fn move_cat_before(&mut self, cat: Cat, other: Cat) -> Result<CatList, CatError> {
+        self.move_cat(cat, |order|position_of(order, &other))
+    }
+
+    ///
+    /// Moves cat right behind the cat other in the display order and returns the cats in their new
+    /// order.
+    ///
+    pub fn move_cat_after(&mut self, cat: Cat, other: Cat) -> Result<CatList, CatError> {
+        self.move_cat(cat, |order|position_of(order, &other).map(|i|i + 1))
+    }
+
+    ///
+    /// Moves cat to index in the display order, or to the end if index is past it, and returns the
+    /// cats in their new order.
+    ///
+    pub fn move_cat_to_index(&mut self, cat: Cat, index: usize) -> Result<CatList, CatError> {
+        self.move_cat(cat, |order|Ok(index.min(order.len())))
+    }
+
+    ///
+    /// Takes cat out of the current display order, puts it back at the index returned by index_of
+    /// for the remaining order and stores the result. Every cat has a position afterwards.
+    /// The order is stored as a list node whose ID is derived from the cats in it, so the latest
+    /// order stored wins.
+    ///
+    fn move_cat<F>(&mut self, cat: Cat, index_of: F) -> Result<CatList, CatError> where F: FnOnce(&[String]) -> Result<usize, CatError> {
+
+        let (_, cats) = self.load_live(MAIN_TABLE.to_string())?;
+        let mut order: Vec<String> = cats.clone().into_iter().map(|cat|cat.triple_id).collect();
+        let from = position_of(&order, &cat)?;
+        order.remove(from);
+        let to = index_of(&order)?;
+        order.insert(to, cat.triple_id);
+
+        let list_id = hashing_tools::hash_text(&format!("{} {}", self.position_tag, order.join(" ")));
+        let list = self.store.create_infotriple(list_id, self.position_tag.clone())?;
+        for cat_id in order.iter() {
+            self.store.create_infotriple(list.id.clone(), cat_id.clone())?;
+        }
+
+        Ok(apply_order(cats, &order))
+    }
+
+    ///
+    /// Sets the positions of cats according to the display order stored last in info_triples.
+    ///
+    pub(super) fn apply_positions(&self, cats: CatList, info_triples: &[InfoTriple]) -> CatList {
+        let list_id = info_triples
+            .iter()
+            .rev()
+            .find_map(|it|it.other_half(self.position_tag.clone()).ok());
+        let order = match list_id {
+            Some(list_id) => self.get_decoration_values(info_triples, self.position_tag.clone())
+                .remove(&list_id)
+                .unwrap_or_default(),
+            None => return cats,
+        };
+        apply_order(cats, &order)
+    }
+}
+
+///
+/// Returns the index of cat in order, failing with CatError::UnknownCat if it is not in there.
+///
+fn position_of(order: &[String], cat: &Cat) -> Result<usize, CatError> {
+    order
+        .iter()
+        .position(|cat_id|*cat_id == cat.triple_id)
+        .ok_or_else(|| CatError::UnknownCat(cat.triple_id.clone()))
+}
+
+///
+/// Numbers the cats in cats in the order of the triple IDs in order, leaving out IDs not in cats.
+/// Cats not in order are left without a position.
+/// The order is indexed first, as this runs every time cats are loaded.
+///
+fn apply_order(cats: CatList, order: &[String]) -> CatList {
+    let cats: Vec<Cat> = cats.into_iter().collect();
+    let positions: HashMap<&String, usize> = {
+        let live: HashSet<&String> = cats.iter().map(|cat|&cat.triple_id).collect();
+        let mut positions: HashMap<&String, usize> = HashMap::new();
+        order
+            .iter()
+            .filter(|cat_id|live.contains(cat_id))
+            .for_each(|cat_id|{
+                let next = positions.len();
+                positions.entry(cat_id).or_insert(next);
+            });
+        positions
+    };
+
+    let mut result = CatList::new();
+    cats.into_iter()
+        .map(|mut cat|{
+            cat.position = positions.get(&cat.triple_id).copied();
+            cat
+        })
+        .for_each(|cat|result.add(cat));
+    result
+}
+
+#[test]
+fn move_cat_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let todo = cat_service.create_cat("a", "Todo", "", "").unwrap();
+    let doing = cat_service.create_cat("b", "Doing", "", "").unwrap();
+    let done = cat_service.create_cat("c", "Done", "", "").unwrap();
+    assert_eq!(cat_names(cat_service.get_all_cats(graph()).unwrap()), vec!["Doing", "Done", "Todo"]);
+
+    let moved = cat_service.move_cat_to_index(todo.clone(), 0).unwrap();
+    assert_eq!(cat_names(moved), vec!["Todo", "Doing", "Done"]);
+    let cats = cat_service.get_all_cats(graph()).unwrap();
+    assert_eq!(cats.get("a".to_string()).unwrap().position, Some(0));
+    assert_eq!(cat_names(cats), vec!["Todo", "Doing", "Done"]);
+
+    cat_service.move_cat_after(done.clone(), todo.clone()).unwrap();
+    assert_eq!(cat_names(cat_service.get_all_cats(graph()).unwrap()), vec!["Todo", "Done", "Doing"]);
+    cat_service.move_cat_before(doing.clone(), done.clone()).unwrap();
+    assert_eq!(cat_names(cat_service.get_all_cats(graph()).unwrap()), vec!["Todo", "Doing", "Done"]);
+    cat_service.move_cat_to_index(todo.clone(), 10).unwrap();
+    assert_eq!(cat_names(cat_service.get_all_cats(graph()).unwrap()), vec!["Doing", "Done", "Todo"]);
+
+    // new cats come after the placed ones, deleted cats leave no gap
+    cat_service.create_cat("d", "Blocked", "", "").unwrap();
+    cat_service.delete_cat(doing.clone()).unwrap();
+    let cats = cat_service.get_all_cats(graph()).unwrap();
+    assert_eq!(cats.get("c".to_string()).unwrap().position, Some(0));
+    assert_eq!(cats.get("d".to_string()).unwrap().position, None);
+    assert_eq!(cat_names(cats), vec!["Done", "Todo", "Blocked"]);
+    assert_eq!(cat_service.move_cat_before(todo.clone(), doing.clone()).unwrap_err(), CatError::UnknownCat(doing.triple_id));
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use infograph::framework::ig_tools::hashing_tools;
+use infograph::InfoTriple;
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, MAIN_TABLE, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_list::CatList;
+use crate::model::cat_value::CatValue;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Sets the property key of cat to value and returns the cat with the property set.
+    /// Fails with CatError::InvalidValue if key is empty, or if key or a text value starts or ends
+    /// with whitespace or holds a line break, as those would not read back the same.
+    ///
+    pub fn set_cat_property(&mut self, cat: Cat, key: &str, value: CatValue) -> Result<Cat, CatError> {
+        check_id(&cat.triple_id)?;
+        self.set_keyed_value(cat.triple_id.clone(), self.property_tag.clone(), key, &value)?;
+
+        let mut result = cat;
+        result.properties.insert(key.to_string(), value);
+        Ok(result)
+    }
+
+    ///
+    /// Removes the property key of cat and returns the cat without it.
+    ///
+    pub fn remove_cat_property(&mut self, cat: Cat, key: &str) -> Result<Cat, CatError> {
+        check_id(&cat.triple_id)?;
+        self.remove_keyed_value(cat.triple_id.clone(), self.property_tag.clone(), key)?;
+
+        let mut result = cat;
+        result.properties.remove(key);
+        Ok(result)
+    }
+
+    ///
+    /// Returns the value of the property key of cat if it has been set.
+    ///
+    pub fn get_cat_property(&mut self, graph_name: String, cat: &Cat, key: &str) -> Result<Option<CatValue>, CatError> {
+        Ok(self.get_cat_properties(graph_name, cat)?.remove(key))
+    }
+
+    ///
+    /// Returns all properties of cat keyed by name.
+    ///
+    pub fn get_cat_properties(&mut self, graph_name: String, cat: &Cat) -> Result<HashMap<String, CatValue>, CatError> {
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let info_triples = self.live_info_triples(info_triples);
+        Ok(self.get_keyed_values(&info_triples, self.property_tag.clone())?
+            .remove(&cat.triple_id)
+            .unwrap_or_default())
+    }
+
+    ///
+    /// Like get_all_cats, but with the properties of all cats loaded in one go.
+    ///
+    pub fn get_all_cats_w_properties(&mut self, graph_name: String) -> Result<CatList, CatError> {
+
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let mut properties = self.get_keyed_values(&info_triples, self.property_tag.clone())?;
+
+        let mut cat_list = CatList::new();
+        cats.into_iter()
+            .map(|mut cat|{
+                cat.properties = properties.remove(&cat.triple_id).unwrap_or_default();
+                cat
+            })
+            .for_each(|cat|cat_list.add(cat));
+
+        Ok(cat_list)
+    }
+
+    ///
+    /// Attaches value under key to the ID decorated_id using the decoration tag.
+    /// Each key/value pair is stored as a descriptor note, name being the key, label the type and
+    /// description the value, at a point derived from tag, key and value. That point is paired with
+    /// the decoration, the pair appended last wins for a key.
+    /// Descriptor notes are stored trimmed and line by line, so keys and values that would not read
+    /// back the same are rejected with CatError::InvalidValue.
+    ///
+    pub(super) fn set_keyed_value(&mut self, decorated_id: String, tag: String, key: &str, value: &CatValue) -> Result<(), CatError> {
+        if key.is_empty() {
+            return Err(CatError::InvalidValue("a key cannot be empty".to_string()));
+        }
+        check_storable(key)?;
+        check_storable(&value.to_string())?;
+
+        let value_point = hashing_tools::hash_text(&format!("{} {} {} {}", tag, key, value.type_name(), value));
+        let existing = self.store.get_descs_hashmap_for_list(vec![value_point.clone()])?.remove(&value_point);
+        if existing.is_none_or(|desc|desc.desc_id.is_empty()) {
+            self.store.create_desc(value_point.clone(), key.to_string(), value.type_name().to_string(), value.to_string())?;
+        }
+
+        let decoration = self.store.create_infotriple(decorated_id, tag)?;
+        self.store.create_infotriple(decoration.id, value_point)?;
+        Ok(())
+    }
+
+    ///
+    /// Removes the value under key from decorated_id by canceling every pair of the decoration tag
+    /// of decorated_id with a value for key.
+    ///
+    pub(super) fn remove_keyed_value(&mut self, decorated_id: String, tag: String, key: &str) -> Result<(), CatError> {
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(MAIN_TABLE.to_string())?;
+        let info_triples = self.live_info_triples(info_triples);
+        let decoration_id = match info_triples
+            .iter()
+            .find(|it|it.other_half(tag.clone()).is_ok_and(|decorated|decorated == decorated_id)) {
+            Some(decoration) => decoration.id.clone(),
+            None => return Ok(()),
+        };
+
+        let mut pairs: Vec<(String, String)> = Vec::new();
+        info_triples
+            .iter()
+            .filter_map(|it|it.other_half(decoration_id.clone()).ok().map(|value_point|(it.id.clone(), value_point)))
+            .for_each(|pair|{
+                if !pairs.contains(&pair) {
+                    pairs.push(pair);
+                }
+            });
+
+        let descs = self.store.get_descs_hashmap_for_list(pairs.iter().map(|(_, value_point)|value_point.clone()).collect())?;
+        for (triple_id, value_point) in pairs {
+            if descs.get(&value_point).is_some_and(|desc|desc.name == key) {
+                self.cancel_infotriple(triple_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the key/value pairs attached with the decoration tag in info_triples keyed by the
+    /// decorated ID. The descriptor notes of all values are loaded in one go.
+    ///
+    pub(super) fn get_keyed_values(&self, info_triples: &[InfoTriple], tag: String) -> Result<HashMap<String, HashMap<String, CatValue>>, CatError> {
+
+        let value_points = self.get_decoration_values(info_triples, tag);
+        let mut all_points: Vec<String> = value_points.values().flatten().cloned().collect();
+        all_points.sort();
+        all_points.dedup();
+        let descs = self.store.get_descs_hashmap_for_list(all_points)?;
+
+        value_points
+            .into_iter()
+            .map(|(decorated_id, value_points)|{
+                let mut values: HashMap<String, CatValue> = HashMap::new();
+                for value_point in value_points {
+                    let desc = descs.get(&value_point)
+                        .filter(|desc|!desc.desc_id.is_empty())
+                        .ok_or_else(|| CatError::MissingDescriptor(value_point.clone()))?;
+                    values.insert(desc.name.clone(), CatValue::parse(&desc.label, &desc.description)?);
+                }
+                Ok((decorated_id, values))
+            })
+            .collect()
+    }
+}
+
+///
+/// Checks that text is kept exactly when written to a field of a descriptor note.
+///
+pub(super) fn check_storable(text: &str) -> Result<(), CatError> {
+    if text.trim() != text || text.contains(['\n', '\r']) {
+        return Err(CatError::InvalidValue(format!("{:?} would not be stored exactly, surrounding whitespace and line breaks are not kept", text)));
+    }
+    Ok(())
+}
+
+#[test]
+fn cat_property_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let todo = cat_service.create_cat("a", "Todo", "", "").unwrap();
+    let done = cat_service.create_cat("b", "Done", "", "").unwrap();
+
+    let todo = cat_service.set_cat_property(todo, "colour", CatValue::from("red")).unwrap();
+    let todo = cat_service.set_cat_property(todo, "shortcut", CatValue::from("ctrl t")).unwrap();
+    let todo = cat_service.set_cat_property(todo, "visible", CatValue::from(true)).unwrap();
+    cat_service.set_cat_property(done.clone(), "colour", CatValue::from("red")).unwrap();
+    let todo = cat_service.set_cat_property(todo, "colour", CatValue::from("blue")).unwrap();
+    assert_eq!(todo.properties.len(), 3);
+    assert_eq!(cat_service.get_cat_properties(graph(), &todo).unwrap(), todo.properties);
+    assert_eq!(cat_service.get_cat_property(graph(), &todo, "colour").unwrap(), Some(CatValue::from("blue")));
+    assert_eq!(cat_service.get_cat_property(graph(), &todo, "owner").unwrap(), None);
+
+    let cats = cat_service.get_all_cats_w_properties(graph()).unwrap();
+    assert_eq!(cats.get("a".to_string()).unwrap().properties, todo.properties);
+    assert_eq!(cats.get("b".to_string()).unwrap().properties.get("colour"), Some(&CatValue::from("red")));
+    assert!(cat_service.get_all_cats(graph()).unwrap().get("a".to_string()).unwrap().properties.is_empty());
+
+    let todo = cat_service.remove_cat_property(todo, "colour").unwrap();
+    assert_eq!(cat_service.get_cat_property(graph(), &todo, "colour").unwrap(), None);
+    assert_eq!(cat_service.get_cat_properties(graph(), &todo).unwrap().len(), 2);
+    // setting a value the property had before
+    cat_service.set_cat_property(todo.clone(), "colour", CatValue::from("red")).unwrap();
+    assert_eq!(cat_service.get_cat_property(graph(), &todo, "colour").unwrap(), Some(CatValue::from("red")));
+
+    assert_eq!(cat_service.set_cat_property(todo, "", CatValue::Integer(1)).unwrap_err(),
+        CatError::InvalidValue("a key cannot be empty".to_string()));
+}
+
+#[test]
+fn cat_property_fs_test() {
+    use crate::cat_service::test_tools::*;
+
+    let (mut cat_service, _guard) = scratch_service_fs("cat_property_fs_test");
+    let todo = cat_service.create_cat("property_fs_a", "Todo", "", "").unwrap();
+    let values = [
+        ("colour", CatValue::from("dark  red")),
+        ("limit", CatValue::Integer(-12)),
+        ("weight", CatValue::Decimal(0.25)),
+        ("visible", CatValue::Bool(false)),
+    ];
+    for (key, value) in values.iter() {
+        cat_service.set_cat_property(todo.clone(), key, value.clone()).unwrap();
+    }
+    let properties = cat_service.get_cat_properties(graph(), &todo).unwrap();
+    for (key, value) in values.iter() {
+        assert_eq!(properties.get(*key), Some(value));
+    }
+    let loaded = cat_service.get_all_cats_w_properties(graph()).unwrap().get("property_fs_a".to_string()).unwrap();
+    assert_eq!(loaded.properties, properties);
+
+    // text that would come back changed is not stored
+    for (key, value) in [("colour", " red"), ("colour", "red\n"), ("colour", "two\nlines"), (" colour", "red")] {
+        assert!(matches!(cat_service.set_cat_property(todo.clone(), key, CatValue::from(value)).unwrap_err(), CatError::InvalidValue(..)));
+    }
+    assert_eq!(cat_service.get_cat_property(graph(), &todo, "colour").unwrap(), Some(CatValue::from("dark  red")));
+}
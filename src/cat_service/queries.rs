@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+
+use infograph::types::Point;
+use infograph::InfoTriple;
+
+use crate::cat_error::CatError;
+use crate::cat_service::conditions::check_expr;
+use crate::cat_service::CatService;
+use crate::model::cat_element::CatElement;
+use crate::model::cat_element_list::CatElementList;
+use crate::model::cat_expr::CatExpr;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Loads and returns the elements whose cats satisfy query, like "tagged with work and urgent,
+    /// but not with done", populated with the descriptor notes of the tagged points. The leaves of
+    /// query are cat triple IDs. Only points tagged with at least one cat are considered.
+    /// Each point is returned once, as its tag with the first cat of query it is tagged with, or
+    /// with its tag appended first if it only satisfies query by not being tagged.
+    /// The info table is read once and the descriptor notes are loaded in one go. Fails with
+    /// CatError::UnknownCat if a cat in query is not a cat currently in the space and with
+    /// CatError::InvalidExpression if query has an empty And or Or.
+    ///
+    pub fn query_cat_elements(&mut self, graph_name: String, query: &CatExpr) -> Result<CatElementList, CatError> {
+        check_expr(query)?;
+
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let query_cat_ids = query.cat_ids();
+        for cat_id in query_cat_ids.iter() {
+            if cats.get_by_triple_id(cat_id.clone()).is_none() {
+                return Err(CatError::UnknownCat(cat_id.clone()));
+            }
+        }
+
+        // the tags of each point in the order they were appended
+        let cat_ids: HashMap<Point, String> = cats
+            .into_iter()
+            .map(|cat|(cat.point, cat.triple_id))
+            .collect();
+        let mut tagged: HashMap<Point, Vec<(&String, &String, &InfoTriple)>> = HashMap::new();
+        info_triples
+            .iter()
+            .filter(|it|it.id1 != self.cat_tag && it.id2 != self.cat_tag)
+            .for_each(|it|{
+                [(&it.id1, &it.id2), (&it.id2, &it.id1)]
+                    .iter()
+                    .filter_map(|(cat_point, point)|cat_ids.get(*cat_point).map(|cat_id|(cat_id, *cat_point, point)))
+                    .for_each(|(cat_id, cat_point, point)|{
+                        let tags = tagged.entry(point.to_string()).or_default();
+                        if !tags.iter().any(|(_, _, tag)|tag.id == it.id) {
+                            tags.push((cat_id, cat_point, it));
+                        }
+                    });
+            });
+
+        let mut matches: Vec<(Point, CatElement)> = Vec::new();
+        for (point, tags) in tagged {
+            let present: HashSet<String> = tags.iter().map(|(cat_id, _, _)|cat_id.to_string()).collect();
+            if !query.eval(&present) {
+                continue;
+            }
+            let (_, cat_point, it) = query_cat_ids
+                .iter()
+                .find_map(|query_cat_id|tags.iter().find(|(cat_id, _, _)|*cat_id == query_cat_id))
+                .unwrap_or(&tags[0]);
+            matches.push((point, CatElement::from_triple(cat_point.to_string(), (*it).clone())?));
+        }
+
+        let mut descs = self.store.get_descs_hashmap_for_list(matches.iter().map(|(point, _)|point.clone()).collect())?;
+        let mut result = CatElementList::new();
+        for (point, mut cat_element) in matches {
+            if let Some(desc) = descs.remove(&point) {
+                cat_element.add_desc(desc);
+            }
+            result.add(cat_element);
+        }
+        Ok(result)
+    }
+}
+
+#[test]
+fn query_cat_elements_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let work = cat_service.create_cat("a", "Work", "", "").unwrap();
+    let urgent = cat_service.create_cat("b", "Urgent", "", "").unwrap();
+    let done = cat_service.create_cat("c", "Done", "", "").unwrap();
+    cat_service.store.create_desc("task1".to_string(), "Task 1".to_string(), "".to_string(), "".to_string()).unwrap();
+    cat_service.create_cat_element(urgent.clone(), "task1".to_string()).unwrap();
+    cat_service.create_cat_element(work.clone(), "task1".to_string()).unwrap();
+    cat_service.create_cat_element(work.clone(), "task2".to_string()).unwrap();
+    cat_service.create_cat_element(urgent.clone(), "task2".to_string()).unwrap();
+    cat_service.create_cat_element(done.clone(), "task2".to_string()).unwrap();
+    cat_service.create_cat_element(work.clone(), "task3".to_string()).unwrap();
+    cat_service.create_cat_element(done.clone(), "task4".to_string()).unwrap();
+    let not = |expr: CatExpr| CatExpr::Not(Box::new(expr));
+
+    let open_urgent = CatExpr::And(vec![on(&work), on(&urgent), not(on(&done))]);
+    let found = cat_service.query_cat_elements(graph(), &open_urgent).unwrap();
+    let task1 = found.clone().into_iter().next().unwrap();
+    assert_eq!(task1.name, "Task 1");
+    assert_eq!(task1.cat_id, work.point);
+    assert_eq!(element_points(found), vec!["task1"]);
+
+    assert_eq!(element_points(cat_service.query_cat_elements(graph(), &CatExpr::Or(vec![on(&urgent), on(&done)])).unwrap()),
+        vec!["task1", "task2", "task4"]);
+    let not_work = cat_service.query_cat_elements(graph(), &not(on(&work))).unwrap();
+    assert_eq!(not_work.clone().into_iter().next().unwrap().cat_id, done.point);
+    assert_eq!(element_points(not_work), vec!["task4"]);
+
+    cat_service.delete_cat(done.clone()).unwrap();
+    assert_eq!(cat_service.query_cat_elements(graph(), &on(&done)).unwrap_err(), CatError::UnknownCat(done.triple_id));
+    assert!(matches!(cat_service.query_cat_elements(graph(), &CatExpr::Or(vec![])).unwrap_err(), CatError::InvalidExpression(..)));
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use infograph::framework::ig_desc::descriptor_tools;
+use infograph::{InfoTriple, Descriptor};
+
+use crate::cat_error::CatError;
+use crate::cat_service::{CatService, MAIN_TABLE, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_list::CatList;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Revises the name, icon and description of a cat and returns the updated cat.
+    /// The cat keeps its point, triple_id and all of its elements and conditions. The new values go
+    /// into a descriptor note of their own, which is paired with the revised decoration of the
+    /// cat's triple, so earlier revisions stay retrievable through get_cat_revisions.
+    /// Fails with CatError::UnknownCat if cat is not a cat currently in the space.
+    ///
+    pub fn update_cat(&mut self, cat: Cat, cat_name: &str, cat_icon: &str, cat_desc: &str) -> Result<Cat, CatError> {
+        check_id(&cat.triple_id)?;
+        let (_, cats) = self.load_live(MAIN_TABLE.to_string())?;
+        if cats.get_by_triple_id(cat.triple_id.clone()).is_none() {
+            return Err(CatError::UnknownCat(cat.triple_id));
+        }
+
+        // going back to an earlier revision reuses its descriptor note
+        let revision_point = descriptor_tools::create_desc_id2(&cat.point, cat_name, cat_icon, cat_desc);
+        let desc = match self.store.get_descs_hashmap_for_list(vec![revision_point.clone()])?.remove(&revision_point) {
+            Some(desc) if !desc.desc_id.is_empty() => desc,
+            _ => self.store.create_desc(revision_point.clone(), 
+                                        cat_name.to_string(), cat_icon.to_string(), cat_desc.to_string())?,
+        };
+        let revised = self.store.create_infotriple(cat.triple_id.clone(), self.revised_tag.clone())?;
+        self.create_stamped_infotriple(revised.id, revision_point)?;
+
+        let mut result = cat;
+        result.desc_id = desc.desc_id;
+        result.name = desc.name;
+        result.icon_uri = desc.label;
+        result.description = desc.description;
+        Ok(result)
+    }
+
+    ///
+    /// Returns all revisions of a cat, oldest first, starting with the descriptor note the cat was
+    /// created with. Each revision is returned as the cat looked at that point.
+    ///
+    pub fn get_cat_revisions(&mut self, graph_name: String, cat: Cat) -> Result<Vec<Cat>, CatError> {
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let info_triples = self.live_info_triples(info_triples);
+
+        let mut points = vec![cat.point.clone()];
+        if let Some(revisions) = self.get_decoration_values(&info_triples, self.revised_tag.clone()).remove(&cat.triple_id) {
+            points.extend(revisions);
+        }
+
+        let descs = self.store.get_descs_hashmap_for_list(points.clone())?;
+        points
+            .iter()
+            .map(|point|{
+                let desc = descs.get(point)
+                    .ok_or_else(|| CatError::MissingDescriptor(point.to_string()))?;
+                Ok(revised(cat.clone(), desc))
+            })
+            .collect()
+    }
+
+    ///
+    /// Returns the point of the latest revision for each revised cat keyed by the cat's triple_id.
+    ///
+    pub(super) fn get_latest_revisions(&self, info_triples: &[InfoTriple]) -> HashMap<String, String> {
+        self.get_decoration_values(info_triples, self.revised_tag.clone())
+            .into_iter()
+            .filter_map(|(cat_triple_id, mut revisions)|{
+                revisions.pop().map(|revision|(cat_triple_id, revision))
+            })
+            .collect()
+    }
+
+    ///
+    /// Replaces the descriptor data of the cats in cats with their latest revision if they have one.
+    ///
+    pub(super) fn apply_revisions(&self, cats: CatList, revisions: &HashMap<String, String>, descs: &HashMap<String, Descriptor>) -> Result<CatList, CatError> {
+        let mut result = CatList::new();
+        for cat in cats {
+            match revisions.get(&cat.triple_id) {
+                Some(revision) => {
+                    let desc = descs.get(revision)
+                        .ok_or_else(|| CatError::MissingDescriptor(revision.to_string()))?;
+                    result.add(revised(cat, desc));
+                },
+                None => result.add(cat),
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn revised(mut cat: Cat, desc: &Descriptor) -> Cat {
+    cat.desc_id = desc.desc_id.clone();
+    cat.name = desc.name.clone();
+    cat.icon_uri = desc.label.clone();
+    cat.description = desc.description.clone();
+    cat
+}
+
+#[test]
+fn update_cat_test() {
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    let cat = cat_service.create_cat("a", "name1", "uri1", "cat no. 1").unwrap();
+    cat_service.create_cat_element(cat.clone(), "note1".to_string()).unwrap();
+
+    let updated = cat_service.update_cat(cat.clone(), "name2", "uri2", "cat no. 2").unwrap();
+    assert_eq!(updated.triple_id, cat.triple_id);
+    assert_eq!(updated.point, cat.point);
+    assert_ne!(updated.desc_id, cat.desc_id);
+
+    let loaded = cat_service.get_all_cats(graph()).unwrap().get("a".to_string()).unwrap();
+    assert_eq!(loaded.name, "name2");
+    assert_eq!(loaded.icon_uri, "uri2");
+    assert_eq!(loaded.description, "cat no. 2");
+    assert_eq!(loaded.desc_id, updated.desc_id);
+    let elements = cat_service.get_cat_elements(graph(), "a".to_string()).unwrap();
+    assert_eq!(elements.get_point_ids(), vec!["note1".to_string()]);
+
+    // going back to an earlier revision
+    cat_service.update_cat(loaded.clone(), "name3", "uri3", "cat no. 3").unwrap();
+    cat_service.update_cat(loaded.clone(), "name2", "uri2", "cat no. 2").unwrap();
+    let loaded = cat_service.get_all_cats_w_conditions(graph()).unwrap().get("a".to_string()).unwrap();
+    assert_eq!(loaded.name, "name2");
+
+    let names: Vec<String> = cat_service.get_cat_revisions(graph(), loaded)
+        .unwrap()
+        .into_iter()
+        .map(|cat|cat.name)
+        .collect();
+    assert_eq!(names, vec!["name1", "name2", "name3", "name2"]);
+
+    // deleted and unknown cats cannot be revised
+    let deleted = cat_service.create_cat("b", "name1", "", "").unwrap();
+    cat_service.delete_cat(deleted.clone()).unwrap();
+    assert_eq!(cat_service.update_cat(deleted.clone(), "name2", "", "").unwrap_err(), CatError::UnknownCat(deleted.triple_id.clone()));
+    let unknown = Cat { triple_id: "x".to_string(), ..cat };
+    assert_eq!(cat_service.update_cat(unknown, "name2", "", "").unwrap_err(), CatError::UnknownCat("x".to_string()));
+    assert_eq!(cat_service.get_cat_revisions(graph(), deleted).unwrap().len(), 1);
+}
+
+#[test]
+fn update_cat_fs_test() {
+    use crate::cat_service::test_tools::*;
+
+    let (mut cat_service, _guard) = scratch_service_fs("update_cat_fs_test");
+    let cat = cat_service.create_cat("update_fs_a", "name1", "uri1", "cat no. 1").unwrap();
+    let updated = cat_service.update_cat(cat.clone(), "name2", "uri2", "cat no. 2").unwrap();
+    assert_eq!(updated.name, "name2");
+
+    let loaded = cat_service.get_all_cats(graph()).unwrap().get("update_fs_a".to_string()).unwrap();
+    assert_eq!((loaded.name.as_str(), loaded.icon_uri.as_str(), loaded.description.as_str()), ("name2", "uri2", "cat no. 2"));
+    assert_eq!(cat_names(cat_service.get_all_cats(graph()).unwrap()), vec!["name2"]);
+    let revisions = cat_service.get_cat_revisions(graph(), loaded).unwrap();
+    let names: Vec<String> = revisions.into_iter().map(|revision|revision.name).collect();
+    assert_eq!(names, vec!["name1", "name2"]);
+
+    // going back to the first revision
+    cat_service.update_cat(updated, "name1", "uri1", "cat no. 1").unwrap();
+    assert_eq!(cat_names(cat_service.get_all_cats(graph()).unwrap()), vec!["name1"]);
+}
@@ -0,0 +1,146 @@
+use infograph::types::Point;
+
+use crate::cat_error::CatError;
+use crate::cat_service::element_values::VALUE_KEY;
+use crate::cat_service::{CatService, MAIN_TABLE, check_id};
+use crate::model::cat::Cat;
+use crate::model::cat_element::CatElement;
+use crate::model::cat_list::CatList;
+use crate::model::cat_tag_outcome::CatTagOutcome;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Splits the cat cat into the cats new_cats, of which point, name, icon_uri and description are
+    /// used, and returns the cats created.
+    /// Each element of cat, loaded with its descriptor note and value, is passed to assign, which
+    /// returns the index in new_cats of the cat to move it to, or None to leave it with cat. Moved
+    /// elements keep their values. If keep_as_parent is true cat becomes the parent of the new cats,
+    /// otherwise it is deleted along with the elements left with it.
+    /// All elements are assigned and all checks are made before the first write, so a failing split
+    /// writes nothing, and all writes of a split share one stamp. Fails with CatError::UnknownCat if cat is not a cat currently in the space and
+    /// with CatError::InvalidValue if a point of new_cats is a cat already, deleted ones included, or
+    /// assign returns an index past new_cats.
+    ///
+    pub fn split_cat<F>(&mut self, cat: Cat, new_cats: Vec<Cat>, keep_as_parent: bool, mut assign: F) -> Result<CatList, CatError> where F: FnMut(&CatElement) -> Option<usize> {
+        check_id(&cat.triple_id)?;
+
+        let all_info_triples = self.store.get_all_info_triples_from_info_table(MAIN_TABLE.to_string())?;
+        let cat_points = self.get_all_cat_points(&all_info_triples);
+        let (info_triples, cats) = self.load_live_from(all_info_triples)?;
+        if cats.get_by_triple_id(cat.triple_id.clone()).is_none() {
+            return Err(CatError::UnknownCat(cat.triple_id));
+        }
+        for (i, new_cat) in new_cats.iter().enumerate() {
+            check_id(&new_cat.point)?;
+            if cat_points.contains(&new_cat.point) || new_cats[..i].iter().any(|other|other.point == new_cat.point) {
+                return Err(CatError::InvalidValue(format!("{} is a cat already", new_cat.point)));
+            }
+        }
+
+        let mut values = self.get_keyed_values(&info_triples, self.valued_tag.clone())?;
+        let mut moves: Vec<(CatElement, usize)> = Vec::new();
+        for mut cat_element in self.select_cat_elements(&info_triples, cat.point.clone())? {
+            cat_element.value = values.remove(&cat_element.element_id).and_then(|mut values|values.remove(VALUE_KEY));
+            match assign(&cat_element) {
+                Some(i) if i >= new_cats.len() => return Err(CatError::InvalidValue(format!("no new cat at index {}", i))),
+                Some(i) => moves.push((cat_element, i)),
+                None => {},
+            }
+        }
+
+        let stamp = self.create_stamp()?;
+        let mut cats = cats;
+        let mut created: Vec<Cat> = Vec::new();
+        for new_cat in new_cats {
+            let new_cat = self.write_cat(&new_cat.point, &new_cat.name, &new_cat.icon_uri, &new_cat.description, &stamp)?;
+            if keep_as_parent {
+                let decoration = self.store.create_infotriple(new_cat.triple_id.clone(), self.parent_tag.clone())?;
+                self.store.create_infotriple(decoration.id, cat.triple_id.clone())?;
+            }
+            cats.add(new_cat.clone());
+            created.push(new_cat);
+        }
+
+        let (pairs, moved): (Vec<(Cat, Point)>, Vec<CatElement>) = moves
+            .into_iter()
+            .map(|(cat_element, i)|((created[i].clone(), cat_element.point.clone()), cat_element))
+            .unzip();
+        let outcomes = self.write_tags(&info_triples, &cats, pairs, &mut Some(stamp.clone()))?;
+        for (outcome, cat_element) in outcomes.into_iter().zip(moved) {
+            match (outcome, cat_element.value) {
+                (CatTagOutcome::Failed(error), _) => return Err(error),
+                (CatTagOutcome::Written(moved), Some(value)) => self.set_keyed_value(moved.element_id, self.valued_tag.clone(), VALUE_KEY, &value)?,
+                _ => {},
+            }
+            let canceled = self.store.create_infotriple(cat_element.element_id, self.canceled_tag.clone())?;
+            self.write_stamp(canceled.id, stamp.1.clone())?;
+        }
+        if !keep_as_parent {
+            let canceled = self.store.create_infotriple(cat.triple_id, self.canceled_tag.clone())?;
+            self.write_stamp(canceled.id, stamp.1)?;
+        }
+
+        let mut result = CatList::new();
+        created.into_iter().for_each(|new_cat|result.add(new_cat));
+        Ok(result)
+    }
+}
+
+#[test]
+fn split_cat_test() {
+    use crate::cat_service::test_tools::*;
+    use crate::model::cat_value::CatValue;
+
+    let mut cat_service = scratch_service();
+    let infra = cat_service.create_cat("a", "Infra", "", "").unwrap();
+    cat_service.create_cat_element_w_value(infra.clone(), "dns1".to_string(), CatValue::Integer(3)).unwrap();
+    cat_service.create_cat_element(infra.clone(), "dns2".to_string()).unwrap();
+    cat_service.create_cat_element(infra.clone(), "ci1".to_string()).unwrap();
+    cat_service.create_cat_element(infra.clone(), "misc".to_string()).unwrap();
+    let new_cat = |point: &str, name: &str| Cat { point: point.to_string(), name: name.to_string(), ..Cat::new() };
+    let assign = |cat_element: &CatElement| match &cat_element.point[..] {
+        "misc" => None,
+        point if point.starts_with("dns") => Some(0),
+        _ => Some(1),
+    };
+
+    // nothing is written when a split fails
+    assert_eq!(cat_service.split_cat(infra.clone(), vec![new_cat("b", "Network")], true, assign).unwrap_err(),
+        CatError::InvalidValue("no new cat at index 1".to_string()));
+    assert_eq!(cat_service.split_cat(infra.clone(), vec![new_cat("a", "Network")], true, assign).unwrap_err(),
+        CatError::InvalidValue("a is a cat already".to_string()));
+    assert_eq!(cat_service.get_all_cats(graph()).unwrap().into_iter().count(), 1);
+
+    let split = cat_service.split_cat(infra.clone(), vec![new_cat("b", "Network"), new_cat("c", "Build")], true, assign).unwrap();
+    let stamp = split.clone().into_iter().next().unwrap().stamp;
+    assert!(stamp.is_some());
+    assert!(split.clone().into_iter().all(|cat|cat.stamp == stamp));
+    let names: Vec<String> = split.into_iter().map(|cat|cat.name).collect();
+    assert_eq!(names, vec!["Build", "Network"]);
+    // the new cats, the moves and the removals share one stamp
+    for cat_id in ["b", "c"] {
+        let moved = cat_service.get_cat_elements_w_stamps(graph(), cat_id.to_string(), None).unwrap();
+        assert!(moved.into_iter().all(|ce|ce.stamp == stamp));
+    }
+    let all_cats = cat_service.get_all_cats_w_stamps(graph()).unwrap();
+    assert_eq!(all_cats.get("b".to_string()).unwrap().stamp, stamp);
+
+    let network = cat_service.get_cat_elements_w_values(graph(), "b".to_string(), None, None, false).unwrap();
+    assert_eq!(network.clone().into_iter().next().unwrap().value, Some(CatValue::Integer(3)));
+    assert_eq!(element_points(network), vec!["dns1", "dns2"]);
+    assert_eq!(element_points(cat_service.get_cat_elements(graph(), "c".to_string()).unwrap()), vec!["ci1"]);
+    assert_eq!(element_points(cat_service.get_cat_elements(graph(), "a".to_string()).unwrap()), vec!["misc"]);
+    assert_eq!(cat_service.get_cat_children(graph(), infra.clone()).unwrap().into_iter().count(), 2);
+
+    let build = cat_service.get_all_cats(graph()).unwrap().get("c".to_string()).unwrap();
+    let ci = cat_service.split_cat(build.clone(), vec![new_cat("d", "CI")], false, |_|Some(0)).unwrap().get("d".to_string()).unwrap();
+    assert_eq!(cat_service.get_cancellation_stamp(graph(), build.triple_id.clone()).unwrap(), ci.stamp);
+    let cats = cat_service.get_all_cats(graph()).unwrap();
+    assert!(cats.get("c".to_string()).is_none());
+    assert_eq!(element_points(cat_service.get_cat_elements(graph(), "d".to_string()).unwrap()), vec!["ci1"]);
+    // the point of a deleted cat is taken as well
+    assert_eq!(cat_service.split_cat(infra, vec![new_cat("c", "Build")], true, |_|None).unwrap_err(),
+        CatError::InvalidValue("c is a cat already".to_string()));
+}
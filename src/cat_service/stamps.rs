@@ -0,0 +1,285 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use infograph::framework::ig_tools::hashing_tools;
+use infograph::{InfoTriple, Descriptor};
+
+use crate::cat_error::CatError;
+use crate::cat_service::CatService;
+use crate::cat_service::properties::check_storable;
+use crate::model::cat_element_list::CatElementList;
+use crate::model::cat_list::CatList;
+use crate::model::cat_stamp::CatStamp;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Sets the ID recorded as actor in the stamps of all writes made from now on, like the name of
+    /// the signed in user. Surrounding whitespace is dropped.
+    /// Fails with CatError::InvalidValue if actor holds a line break, as it would not be read back
+    /// from the stamps the same, and keeps the actor set before.
+    ///
+    pub fn set_actor(&mut self, actor: &str) -> Result<(), CatError> {
+        let actor = actor.trim();
+        check_storable(actor)?;
+        self.actor = actor.to_string();
+        Ok(())
+    }
+
+    ///
+    /// Returns the stamp of the last write of the triple triple_id, if it has been stamped. Works
+    /// for the triple IDs of cats, cat elements and conditions.
+    ///
+    pub fn get_stamp(&mut self, graph_name: String, triple_id: String) -> Result<Option<CatStamp>, CatError> {
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let info_triples = self.live_info_triples(info_triples);
+        Ok(self.get_stamps(&info_triples, std::slice::from_ref(&triple_id))?.remove(&triple_id))
+    }
+
+    ///
+    /// Returns the stamp of the cancellation of the triple triple_id, telling who deleted a cat or
+    /// removed a cat element or condition and when. None if the triple is not canceled right now.
+    ///
+    pub fn get_cancellation_stamp(&mut self, graph_name: String, triple_id: String) -> Result<Option<CatStamp>, CatError> {
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let info_triples = self.live_info_triples(info_triples);
+        let canceled_id = match info_triples
+            .iter()
+            .find(|it|it.other_half(self.canceled_tag.clone()).is_ok_and(|target|target == triple_id)) {
+            Some(canceled) => canceled.id.clone(),
+            None => return Ok(None),
+        };
+        Ok(self.get_stamps(&info_triples, std::slice::from_ref(&canceled_id))?.remove(&canceled_id))
+    }
+
+    ///
+    /// Like get_all_cats, but with the stamps of all cats loaded in one go.
+    ///
+    pub fn get_all_cats_w_stamps(&mut self, graph_name: String) -> Result<CatList, CatError> {
+
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let triple_ids: Vec<String> = cats.clone().into_iter().map(|cat|cat.triple_id).collect();
+        let mut stamps = self.get_stamps(&info_triples, &triple_ids)?;
+
+        let mut cat_list = CatList::new();
+        cats.into_iter()
+            .map(|mut cat|{
+                cat.stamp = stamps.remove(&cat.triple_id);
+                cat
+            })
+            .for_each(|cat|cat_list.add(cat));
+
+        Ok(cat_list)
+    }
+
+    ///
+    /// Like get_cat_elements, but with the stamps of the elements loaded and the list sorted by the
+    /// time they were tagged, most recent first. If since is given only the elements tagged at
+    /// that time or later are returned.
+    ///
+    pub fn get_cat_elements_w_stamps(&mut self, graph_name: String, cat_id: String, since: Option<u64>) -> Result<CatElementList, CatError> {
+
+        let info_triples = self.store.get_all_info_triples_from_info_table(graph_name)?;
+        let info_triples = self.live_info_triples(info_triples);
+        let cat_elements = self.select_cat_elements(&info_triples, cat_id)?;
+        let element_ids: Vec<String> = cat_elements.clone().into_iter().map(|ce|ce.element_id).collect();
+        let mut stamps = self.get_stamps(&info_triples, &element_ids)?;
+
+        let mut result = CatElementList::new();
+        cat_elements
+            .into_iter()
+            .map(|mut cat_element|{
+                cat_element.stamp = stamps.remove(&cat_element.element_id);
+                cat_element
+            })
+            .filter(|cat_element|since.is_none_or(|since|cat_element.stamp.as_ref().is_some_and(|stamp|stamp.time >= since)))
+            .for_each(|cat_element|result.add(cat_element));
+
+        result.sort_by(|a, b|b.stamp.cmp(&a.stamp));
+        Ok(result)
+    }
+
+    ///
+    /// Appends a new info triple pairing id1 and id2 like CatStore::create_infotriple and stamps it
+    /// with the actor and the current time. Returns the triple and its stamp.
+    /// The stamp is a descriptor note at a point derived from actor and time. That point is paired
+    /// with the stamped decoration of the triple, the pair appended last wins.
+    ///
+    pub(super) fn create_stamped_infotriple(&mut self, id1: String, id2: String) -> Result<(InfoTriple, CatStamp), CatError> {
+        let it = self.store.create_infotriple(id1, id2)?;
+        let (stamp, stamp_point) = self.create_stamp()?;
+        self.write_stamp(it.id.clone(), stamp_point)?;
+        Ok((it, stamp))
+    }
+
+    ///
+    /// Writes the descriptor note of a stamp with the actor and the current time and returns the
+    /// stamp and its point, to be paired with triples using write_stamp.
+    ///
+    pub(super) fn create_stamp(&mut self) -> Result<(CatStamp, String), CatError> {
+        let stamp = CatStamp {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration|duration.as_millis() as u64)
+                .unwrap_or_default(),
+            actor: self.actor.clone(),
+        };
+
+        let stamp_point = hashing_tools::hash_text(&format!("{} {} {}", self.stamped_tag, stamp.actor, stamp.time));
+        self.store.create_desc(stamp_point.clone(), stamp.actor.clone(), stamp.time.to_string(), "".to_string())?;
+        Ok((stamp, stamp_point))
+    }
+
+    ///
+    /// Stamps the triple triple_id, which has to be the triple appended last, with the stamp at
+    /// stamp_point.
+    ///
+    pub(super) fn write_stamp(&mut self, triple_id: String, stamp_point: String) -> Result<(), CatError> {
+        let stamped = self.store.create_infotriple(triple_id, self.stamped_tag.clone())?;
+        self.store.create_infotriple(stamped.id, stamp_point)?;
+        Ok(())
+    }
+
+    ///
+    /// Cancels the triple triple_id by appending its stamped canceled decoration.
+    ///
+    pub(super) fn cancel_infotriple(&mut self, triple_id: String) -> Result<InfoTriple, CatError> {
+        self.create_stamped_infotriple(triple_id, self.canceled_tag.clone())
+            .map(|(it, _)|it)
+    }
+
+    ///
+    /// Returns the stamps of the triples triple_ids found in info_triples keyed by triple ID.
+    /// The descriptor notes of the stamps are loaded in one go.
+    ///
+    pub(super) fn get_stamps(&self, info_triples: &[InfoTriple], triple_ids: &[String]) -> Result<HashMap<String, CatStamp>, CatError> {
+
+        let triple_ids: HashSet<&String> = triple_ids.iter().collect();
+        let stamp_points: HashMap<String, String> = self.get_decoration_values(info_triples, self.stamped_tag.clone())
+            .into_iter()
+            .filter(|(triple_id, _)|triple_ids.contains(triple_id))
+            .filter_map(|(triple_id, mut stamp_points)|stamp_points.pop().map(|stamp_point|(triple_id, stamp_point)))
+            .collect();
+        let descs = self.store.get_descs_hashmap_for_list(stamp_points.values().cloned().collect())?;
+
+        stamp_points
+            .into_iter()
+            .map(|(triple_id, stamp_point)|Ok((triple_id, read_stamp(&descs, &stamp_point)?)))
+            .collect()
+    }
+
+    ///
+    /// Returns the stamp of each write in info_triples, all triples of an info table in the order
+    /// they were appended, at the index of the triple written. Triples appended without a stamp,
+    /// like decorations, get None. The descriptor notes of the stamps are loaded in one go.
+    ///
+    pub(super) fn get_write_stamps(&self, info_triples: &[InfoTriple]) -> Result<Vec<Option<CatStamp>>, CatError> {
+
+        let mut stamped: HashMap<String, String> = HashMap::new();
+        let mut last_appended: HashMap<String, usize> = HashMap::new();
+        let mut stamp_points: Vec<(usize, String)> = Vec::new();
+        for (i, it) in info_triples.iter().enumerate() {
+            match it.other_half(self.stamped_tag.clone()) {
+                Ok(triple_id) => {
+                    stamped.insert(it.id.clone(), triple_id);
+                },
+                Err(..) => [(&it.id1, &it.id2), (&it.id2, &it.id1)]
+                    .iter()
+                    .filter_map(|(decoration_id, stamp_point)|{
+                        let written = last_appended.get(stamped.get(*decoration_id)?)?;
+                        Some((*written, stamp_point.to_string()))
+                    })
+                    .for_each(|stamp_point|stamp_points.push(stamp_point)),
+            }
+            last_appended.insert(it.id.clone(), i);
+        }
+
+        let descs = self.store.get_descs_hashmap_for_list(stamp_points.iter().map(|(_, stamp_point)|stamp_point.clone()).collect())?;
+        let mut result = vec![None; info_triples.len()];
+        for (i, stamp_point) in stamp_points {
+            result[i] = Some(read_stamp(&descs, &stamp_point)?);
+        }
+        Ok(result)
+    }
+}
+
+///
+/// Reads the stamp at stamp_point from its descriptor note in descs.
+///
+fn read_stamp(descs: &HashMap<String, Descriptor>, stamp_point: &String) -> Result<CatStamp, CatError> {
+    let desc = descs.get(stamp_point)
+        .filter(|desc|!desc.desc_id.is_empty())
+        .ok_or_else(|| CatError::MissingDescriptor(stamp_point.clone()))?;
+    let time = desc.label
+        .parse::<u64>()
+        .map_err(|_| CatError::InvalidValue(format!("\"{}\" is not a time", desc.label)))?;
+    Ok(CatStamp { time, actor: desc.name.clone() })
+}
+
+#[test]
+fn stamp_test() {
+    use std::thread;
+    use std::time::Duration;
+    use crate::cat_service::test_tools::*;
+
+    let mut cat_service = scratch_service();
+    cat_service.set_actor("alice").unwrap();
+    let todo = cat_service.create_cat("a", "Todo", "", "").unwrap();
+    let created = todo.stamp.clone().unwrap();
+    assert_eq!(created.actor, "alice");
+    assert!(created.time > 0);
+    assert_eq!(cat_service.get_stamp(graph(), todo.triple_id.clone()).unwrap(), Some(created.clone()));
+    assert_eq!(cat_service.get_all_cats_w_stamps(graph()).unwrap().get("a".to_string()).unwrap().stamp, Some(created));
+    assert_eq!(cat_service.get_all_cats(graph()).unwrap().get("a".to_string()).unwrap().stamp, None);
+
+    cat_service.set_actor("bob").unwrap();
+    // an actor that would not be read back the same is rejected and bob stays
+    assert!(matches!(cat_service.set_actor("mallory\nbob"), Err(CatError::InvalidValue(..))));
+    cat_service.set_actor("  bob ").unwrap();
+    let note1 = cat_service.create_cat_element(todo.clone(), "note1".to_string()).unwrap();
+    thread::sleep(Duration::from_millis(2));
+    let note2 = cat_service.create_cat_element(todo.clone(), "note2".to_string()).unwrap();
+    assert_eq!(note1.stamp.clone().unwrap().actor, "bob");
+    let recent = cat_service.get_cat_elements_w_stamps(graph(), "a".to_string(), None).unwrap();
+    assert_eq!(recent.clone().into_iter().next().unwrap().stamp, note2.stamp);
+    assert_eq!(element_points(recent), vec!["note2", "note1"]);
+    let since = note2.stamp.clone().unwrap().time;
+    assert_eq!(element_points(cat_service.get_cat_elements_w_stamps(graph(), "a".to_string(), Some(since)).unwrap()), vec!["note2"]);
+
+    let done = cat_service.create_cat("b", "Done", "", "").unwrap();
+    let condition = cat_service.create_cat_condi(done.triple_id.clone(), todo.triple_id.clone()).unwrap();
+    assert_eq!(cat_service.get_cat_condis(graph(), todo.triple_id.clone()).unwrap()[0].stamp, condition.stamp);
+
+    cat_service.set_actor("carol").unwrap();
+    assert_eq!(cat_service.get_cancellation_stamp(graph(), note1.element_id.clone()).unwrap(), None);
+    cat_service.remove_cat_element(&note1).unwrap();
+    cat_service.delete_cat(done.clone()).unwrap();
+    let removed = cat_service.get_cancellation_stamp(graph(), note1.element_id.clone()).unwrap().unwrap();
+    assert_eq!(removed.actor, "carol");
+    assert_eq!(cat_service.get_cancellation_stamp(graph(), done.triple_id.clone()).unwrap().unwrap().actor, "carol");
+
+    // tagging again is stamped anew and takes back the cancellation
+    cat_service.set_actor("dave").unwrap();
+    cat_service.create_cat_element(todo.clone(), "note1".to_string()).unwrap();
+    assert_eq!(cat_service.get_cancellation_stamp(graph(), note1.element_id.clone()).unwrap(), None);
+    assert_eq!(cat_service.get_stamp(graph(), note1.element_id).unwrap().unwrap().actor, "dave");
+}
+
+#[test]
+fn stamp_fs_test() {
+    use crate::cat_service::test_tools::*;
+
+    let (mut cat_service, _guard) = scratch_service_fs("stamp_fs_test");
+    cat_service.set_actor("alice").unwrap();
+    let todo = cat_service.create_cat("stamp_fs_a", "Todo", "", "").unwrap();
+    let note1 = cat_service.create_cat_element(todo.clone(), "stamp_fs_note1".to_string()).unwrap();
+    assert_eq!(cat_service.get_all_cats_w_stamps(graph()).unwrap().get("stamp_fs_a".to_string()).unwrap().stamp, todo.stamp);
+    assert_eq!(cat_service.get_stamp(graph(), note1.element_id.clone()).unwrap(), note1.stamp);
+    let elements = cat_service.get_cat_elements_w_stamps(graph(), "stamp_fs_a".to_string(), None).unwrap();
+    assert_eq!(elements.into_iter().next().unwrap().stamp, note1.stamp);
+
+    cat_service.set_actor("bob").unwrap();
+    cat_service.remove_cat_element(&note1).unwrap();
+    assert_eq!(cat_service.get_cancellation_stamp(graph(), note1.element_id).unwrap().unwrap().actor, "bob");
+}
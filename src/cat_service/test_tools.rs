@@ -0,0 +1,70 @@
+use std::fs;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::cat_service::MAIN_TABLE;
+use crate::cat_service_fs::CatServiceFS;
+use crate::cat_service_mem::CatServiceMem;
+use crate::model::cat::Cat;
+use crate::model::cat_element_list::CatElementList;
+use crate::model::cat_expr::CatExpr;
+use crate::model::cat_list::CatList;
+
+///
+/// The filesystem storage rewrites its shared descriptor indexes on every write, so tests using
+/// it take turns.
+///
+static FS_LOCK: Mutex<()> = Mutex::new(());
+
+///
+/// A CatServiceMem for a test, starting out empty.
+///
+pub(super) fn scratch_service() -> CatServiceMem {
+    CatServiceMem::new("scratch".to_string())
+}
+
+///
+/// A CatServiceFS for a test working on the space space_id, which is cleared first. The space is
+/// the test's own for as long as the returned guard is held.
+/// Descriptor notes are shared by all spaces on the filesystem, so each test has to use points
+/// of its own. The storage writes below infospace/ in the crate root, which git ignores.
+///
+pub(super) fn scratch_service_fs(space_id: &str) -> (CatServiceFS, MutexGuard<'static, ()>) {
+    let guard = FS_LOCK.lock().unwrap_or_else(|poisoned|poisoned.into_inner());
+    let _ = fs::remove_dir_all(format!("infospace/files/spaces/{}", space_id));
+    (CatServiceFS::new(space_id.to_string()), guard)
+}
+
+///
+/// The info table the tests read from.
+///
+pub(super) fn graph() -> String {
+    MAIN_TABLE.to_string()
+}
+
+///
+/// The condition expression made of cat alone.
+///
+pub(super) fn on(cat: &Cat) -> CatExpr {
+    CatExpr::Cat(cat.triple_id.clone())
+}
+
+///
+/// The points of cat_elements in order.
+///
+pub(super) fn element_points(cat_elements: CatElementList) -> Vec<String> {
+    cat_elements.into_iter().map(|ce|ce.point).collect()
+}
+
+///
+/// The points of cats in order.
+///
+pub(super) fn cat_points(cats: CatList) -> Vec<String> {
+    cats.into_iter().map(|cat|cat.point).collect()
+}
+
+///
+/// The names of cats in order.
+///
+pub(super) fn cat_names(cats: CatList) -> Vec<String> {
+    cats.into_iter().map(|cat|cat.name).collect()
+}
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use infograph::types::Point;
+
+use crate::cat_error::CatError;
+use crate::cat_service::CatService;
+use crate::cat_service::point_cats::select_cats_for_points;
+use crate::model::cat_list::CatList;
+use crate::model::cat_visibility::CatVisibility;
+use crate::store::cat_store::CatStore;
+
+impl<S: CatStore> CatService<S> {
+
+    ///
+    /// Returns the cats to offer for point right now: the cats without a condition and the cats
+    /// whose condition is satisfied by the cats point is currently tagged with. The cats point is
+    /// already tagged with are offered as well if their condition holds.
+    ///
+    pub fn visible_cats_for_point(&mut self, graph_name: String, point: Point) -> Result<CatList, CatError> {
+
+        let mut result = CatList::new();
+        self.explain_cats_for_point(graph_name, point)?
+            .into_iter()
+            .filter(|visibility|visibility.is_visible())
+            .for_each(|visibility|result.add(visibility.cat));
+
+        Ok(result)
+    }
+
+    ///
+    /// The explain mode of visible_cats_for_point. Returns a CatVisibility for every cat, in the
+    /// order of CatList, telling whether it is offered for point and if not which part of its
+    /// condition point does not satisfy.
+    ///
+    pub fn explain_cats_for_point(&mut self, graph_name: String, point: Point) -> Result<Vec<CatVisibility>, CatError> {
+
+        let (info_triples, cats) = self.load_live(graph_name)?;
+        let mut conditions = self.get_condition_exprs(&info_triples);
+
+        let tagged: HashSet<String> = select_cats_for_points(info_triples, &cats, vec![point.clone()])
+            .remove(&point)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|cat|cat.triple_id)
+            .collect();
+
+        Ok(cats.into_iter()
+            .map(|mut cat|{
+                cat.condition = conditions.remove(&cat.triple_id);
+                let unmet = cat.condition.as_ref().and_then(|condition|condition.unmet(&tagged));
+                CatVisibility { cat, unmet }
+            })
+            .collect())
+    }
+}
+
+#[test]
+fn visible_cats_for_point_test() {
+    use crate::cat_service::test_tools::*;
+    use crate::model::cat_expr::CatExpr;
+
+    let mut cat_service = scratch_service();
+    let work = cat_service.create_cat("a", "Work", "", "").unwrap();
+    let done = cat_service.create_cat("b", "Done", "", "").unwrap();
+    let escalate = cat_service.create_cat("c", "Escalate", "", "").unwrap();
+    cat_service.create_cat_condi_expr(CatExpr::And(vec![on(&work), CatExpr::Not(Box::new(on(&done)))]),
+        escalate.triple_id.clone()).unwrap();
+    cat_service.create_cat_condi(work.triple_id.clone(), done.triple_id.clone()).unwrap();
+
+
+    assert_eq!(cat_points(cat_service.visible_cats_for_point(graph(), "note1".to_string()).unwrap()), vec!["a"]);
+    let explained = cat_service.explain_cats_for_point(graph(), "note1".to_string()).unwrap();
+    assert_eq!(explained.len(), 3);
+    assert_eq!(explained[0].unmet, Some(on(&work)));
+    assert_eq!(explained[1].unmet, Some(on(&work)));
+    assert!(explained[2].is_visible());
+
+    cat_service.create_cat_element(work.clone(), "note1".to_string()).unwrap();
+    assert_eq!(cat_points(cat_service.visible_cats_for_point(graph(), "note1".to_string()).unwrap()), vec!["b", "c", "a"]);
+
+    cat_service.create_cat_element(done.clone(), "note1".to_string()).unwrap();
+    assert_eq!(cat_points(cat_service.visible_cats_for_point(graph(), "note1".to_string()).unwrap()), vec!["b", "a"]);
+    let explained = cat_service.explain_cats_for_point(graph(), "note1".to_string()).unwrap();
+    assert_eq!(explained[1].unmet, Some(CatExpr::Not(Box::new(on(&done)))));
+}
@@ -0,0 +1,224 @@
+use crate::cat_service::CatService;
+use crate::store::cat_store_mem::CatStoreMem;
+
+///
+/// The CatService working on an in-memory storage. Useful for tests and for scratch spaces that
+/// should never touch the disk.
+///
+pub type CatServiceMem = CatService<CatStoreMem>;
+
+impl CatServiceMem {
+
+    pub fn new(space_id: String) -> Self{
+
+        CatService::from_store(space_id, CatStoreMem::new())
+    }
+}
+
+#[test]
+fn get_all_cats_test() {
+    let mut cat_service = CatServiceMem::new("scratch".to_string());
+    cat_service.create_cat("a", "name1", "uri1", "cat no. 1").unwrap();
+    cat_service.create_cat("b", "name2", "uri2", "cat no. 2").unwrap();
+
+    let cats = cat_service.get_all_cats("main_table".to_string()).unwrap();
+    let mut it = cats.into_iter();
+    let cat = it.next().unwrap();
+    assert_eq!(cat.point, "a");
+    assert_eq!(cat.name, "name1");
+    assert_eq!(cat.icon_uri, "uri1");
+    assert_eq!(cat.description, "cat no. 1");
+    assert!(!cat.desc_id.is_empty());
+    let cat = it.next().unwrap();
+    assert_eq!(cat.point, "b");
+    assert!(it.next().is_none());
+
+    let cat = cat_service.get_all_cats("main_table".to_string()).unwrap().get("a".to_string()).unwrap();
+    cat_service.delete_cat(cat).unwrap();
+    let cats = cat_service.get_all_cats("main_table".to_string()).unwrap();
+    assert!(cats.get("a".to_string()).is_none());
+    assert!(cats.get("b".to_string()).is_some());
+}
+
+#[test]
+fn get_cat_elements_test() {
+    use crate::store::cat_store::CatStore;
+
+    let mut store = CatStoreMem::new();
+    store.create_desc("note1".to_string(), "Note 1".to_string(), "label1".to_string(), "note no. 1".to_string()).unwrap();
+    let mut cat_service = CatService::from_store("scratch".to_string(), store);
+    cat_service.create_cat("a", "name1", "uri1", "cat no. 1").unwrap();
+    let cat = cat_service.get_all_cats("main_table".to_string()).unwrap().get("a".to_string()).unwrap();
+    cat_service.create_cat_element(cat.clone(), "note1".to_string()).unwrap();
+    cat_service.create_cat_element(cat.clone(), "note2".to_string()).unwrap();
+
+    let elements = cat_service.get_cat_elements("main_table".to_string(), cat.point.clone()).unwrap();
+    assert_eq!(elements.get_point_ids().len(), 2);
+    let names = elements.get_all_cat_element_names();
+    assert_eq!(names.get("note1").unwrap(), "Note 1");
+    assert_eq!(names.get("note2").unwrap(), "note2");
+    let mut it = elements.into_iter();
+    let element = it.next().unwrap();
+    assert_eq!(element.point, "note1");
+    assert_eq!(element.name, "Note 1");
+    assert_eq!(element.label, "label1");
+    assert_eq!(element.description, "note no. 1");
+    assert!(!element.desc_id.is_empty());
+    let element = it.next().unwrap();
+    assert_eq!(element.point, "note2");
+    assert!(element.name.is_empty());
+    assert!(element.desc_id.is_empty());
+    assert!(it.next().is_none());
+}
+
+#[test]
+fn create_cat_condi_test() {
+    use crate::model::cat_expr::CatExpr;
+
+    let mut cat_service = CatServiceMem::new("scratch".to_string());
+    cat_service.create_cat("a", "name1", "uri1", "cat no. 1").unwrap();
+    cat_service.create_cat("b", "name2", "uri2", "cat no. 2").unwrap();
+    let cats = cat_service.get_all_cats("main_table".to_string()).unwrap();
+    let super_cat = cats.get("a".to_string()).unwrap();
+    let sub_cat = cats.get("b".to_string()).unwrap();
+    let condition = cat_service.create_cat_condi(super_cat.triple_id.clone(), sub_cat.triple_id.clone()).unwrap();
+    assert_eq!(condition.super_cat_id, super_cat.triple_id);
+    assert_eq!(condition.sub_cat_id, sub_cat.triple_id);
+    assert!(!condition.triple_id.is_empty());
+    assert!(!condition.conditioned_id.is_empty());
+
+    let cats = cat_service.get_all_cats_w_conditions("main_table".to_string()).unwrap();
+    assert!(!cats.get("a".to_string()).unwrap().has_conditions());
+    let sub_cat = cats.get("b".to_string()).unwrap();
+    assert_eq!(sub_cat.condition, Some(CatExpr::Cat(super_cat.triple_id)));
+}
+
+#[test]
+fn remove_cat_element_test() {
+    let mut cat_service = CatServiceMem::new("scratch".to_string());
+    let cat = cat_service.create_cat("a", "name1", "uri1", "cat no. 1").unwrap();
+    let note1 = cat_service.create_cat_element(cat.clone(), "note1".to_string()).unwrap();
+    let note2 = cat_service.create_cat_element(cat.clone(), "note2".to_string()).unwrap();
+    cat_service.create_cat_element(cat.clone(), "note3".to_string()).unwrap();
+
+    cat_service.remove_cat_element(&note1).unwrap();
+    let elements = cat_service.get_cat_elements("main_table".to_string(), "a".to_string()).unwrap();
+    assert_eq!(elements.get_point_ids().len(), 2);
+    assert!(!elements.get_point_ids().contains(&"note1".to_string()));
+
+    let removed = cat_service.remove_cat_elements(elements).unwrap();
+    assert_eq!(removed.get_point_ids().len(), 2);
+    let elements = cat_service.get_cat_elements("main_table".to_string(), "a".to_string()).unwrap();
+    assert!(elements.get_point_ids().is_empty());
+
+    // tagging again after the tag was removed
+    let note2_again = cat_service.create_cat_element(cat.clone(), "note2".to_string()).unwrap();
+    assert_eq!(note2_again.element_id, note2.element_id);
+    let elements = cat_service.get_cat_elements("main_table".to_string(), "a".to_string()).unwrap();
+    assert_eq!(elements.get_point_ids(), vec!["note2".to_string()]);
+}
+
+#[test]
+fn restore_cat_test() {
+    use crate::cat_error::CatError;
+    use crate::model::cat::Cat;
+    use crate::model::cat_expr::CatExpr;
+
+    let mut cat_service = CatServiceMem::new("scratch".to_string());
+    let cat_a = cat_service.create_cat("a", "name1", "uri1", "cat no. 1").unwrap();
+    let cat_b = cat_service.create_cat("b", "name2", "uri2", "cat no. 2").unwrap();
+    cat_service.create_cat_condi(cat_b.triple_id.clone(), cat_a.triple_id.clone()).unwrap();
+    cat_service.create_cat_element(cat_a.clone(), "note1".to_string()).unwrap();
+    assert!(cat_service.get_canceled_cats("main_table".to_string()).unwrap().is_empty());
+
+    cat_service.delete_cat(cat_a.clone()).unwrap();
+    let canceled = cat_service.get_canceled_cats("main_table".to_string()).unwrap();
+    assert_eq!(canceled.get("a".to_string()).unwrap().name, "name1");
+    assert!(canceled.get("b".to_string()).is_none());
+
+    cat_service.restore_cat(cat_a.clone()).unwrap();
+    assert!(cat_service.get_canceled_cats("main_table".to_string()).unwrap().is_empty());
+    let cats = cat_service.get_all_cats_w_conditions("main_table".to_string()).unwrap();
+    assert_eq!(cats.get("a".to_string()).unwrap().condition, Some(CatExpr::Cat(cat_b.triple_id.clone())));
+    let elements = cat_service.get_cat_elements("main_table".to_string(), "a".to_string()).unwrap();
+    assert_eq!(elements.get_point_ids(), vec!["note1".to_string()]);
+
+    // only deleted cats can be restored
+    assert_eq!(cat_service.restore_cat(cat_a.clone()).unwrap_err(),
+        CatError::InvalidValue("a has not been deleted".to_string()));
+    let never = Cat { point: "c".to_string(), ..Cat::new() };
+    assert_eq!(cat_service.restore_cat(never).unwrap_err(), CatError::UnknownCat("c".to_string()));
+    assert!(cat_service.get_all_cats("main_table".to_string()).unwrap().get("c".to_string()).is_none());
+
+    // deleting again after a restore
+    cat_service.delete_cat(cat_a.clone()).unwrap();
+    assert!(cat_service.get_all_cats("main_table".to_string()).unwrap().get("a".to_string()).is_none());
+}
+
+#[test]
+fn create_after_delete_test() {
+    use crate::cat_error::CatError;
+
+    let mut cat_service = CatServiceMem::new("scratch".to_string());
+    let cat = cat_service.create_cat("a", "name1", "uri1", "cat no. 1").unwrap();
+    cat_service.create_cat_element(cat.clone(), "note1".to_string()).unwrap();
+    let already = CatError::InvalidValue("a is a cat already".to_string());
+    assert_eq!(cat_service.create_cat("a", "name2", "", "").unwrap_err(), already);
+
+    // creating a cat does not bring back a deleted one
+    cat_service.delete_cat(cat.clone()).unwrap();
+    assert_eq!(cat_service.create_cat("a", "name2", "", "").unwrap_err(), already);
+    assert!(cat_service.get_all_cats("main_table".to_string()).unwrap().get("a".to_string()).is_none());
+    assert_eq!(cat_service.get_canceled_cats("main_table".to_string()).unwrap().get("a".to_string()).unwrap().name, "name1");
+
+    cat_service.restore_cat(cat).unwrap();
+    let elements = cat_service.get_cat_elements("main_table".to_string(), "a".to_string()).unwrap();
+    assert_eq!(elements.get_point_ids(), vec!["note1".to_string()]);
+}
+
+#[test]
+fn create_returns_created_test() {
+    let mut cat_service = CatServiceMem::new("scratch".to_string());
+    let cat = cat_service.create_cat("a", "name1", "uri1", "cat no. 1").unwrap();
+    let loaded = cat_service.get_all_cats("main_table".to_string()).unwrap().get("a".to_string()).unwrap();
+    assert_eq!(cat.triple_id, loaded.triple_id);
+    assert_eq!(cat.desc_id, loaded.desc_id);
+    assert_eq!(cat.type_id, loaded.type_id);
+    assert_eq!(cat.name, "name1");
+    assert_eq!(cat.icon_uri, "uri1");
+    assert_eq!(cat.description, "cat no. 1");
+
+    cat_service.create_cat("note1", "Note 1", "", "").unwrap();
+    let cat_element = cat_service.create_cat_element(cat.clone(), "note1".to_string()).unwrap();
+    assert_eq!(cat_element.cat_id, "a");
+    assert_eq!(cat_element.point, "note1");
+    assert_eq!(cat_element.name, "Note 1");
+    assert!(!cat_element.desc_id.is_empty());
+    let loaded = cat_service.get_cat_elements("main_table".to_string(), "a".to_string()).unwrap();
+    assert_eq!(loaded.into_iter().next().unwrap().element_id, cat_element.element_id);
+
+    let deleted = cat_service.delete_cat(cat.clone()).unwrap();
+    assert_eq!(deleted.triple_id, cat.triple_id);
+}
+
+#[test]
+fn cat_error_test() {
+    use std::collections::HashMap;
+    use infograph::{InfoTriple, InfoTable};
+    use crate::cat_error::CatError;
+
+    let mut cat_service = CatServiceMem::new("scratch".to_string());
+    assert_eq!(cat_service.create_cat("a b", "name1", "uri1", "cat no. 1").unwrap_err(),
+        CatError::MalformedTriple("\"a b\" cannot be used as an id in an info triple".to_string()));
+    assert_eq!(cat_service.get_cat_elements("main_table".to_string(), "a".to_string()).unwrap_err(),
+        CatError::UnknownCat("a".to_string()));
+
+    let mut info_table: InfoTable = InfoTable::new();
+    let _ = info_table.add_triple(InfoTriple {
+        id: "3-1".to_string(),
+        id1: "a".to_string(),
+        id2: "5fe9374c5e9e27ebb78f8bf7cd78bbb23ee51e672dc54c603ec1c5b3eef33feb".to_string(),
+    });
+    let result = cat_service.populate_cat_list(info_table, HashMap::new());
+    assert_eq!(result.unwrap_err(), CatError::MissingDescriptor("a".to_string()));
+}
@@ -3,11 +3,22 @@
 use std::collections::HashMap;
 
 use infograph::types::{NameOrPoint, Point};
-use infograph::{InfoTriple, Descriptor, InfoTable};
 
+use crate::cat_error::CatError;
 use crate::model::cat::Cat;
+use crate::model::cat_condition::CatCondition;
+use crate::model::cat_condition_issue::CatConditionIssue;
+use crate::model::cat_element::CatElement;
 use crate::model::cat_element_list::CatElementList;
+use crate::model::cat_event::CatEvent;
+use crate::model::cat_expr::CatExpr;
+use crate::model::cat_facet::CatFacet;
 use crate::model::cat_list::CatList;
+use crate::model::cat_parent::CatParent;
+use crate::model::cat_stamp::CatStamp;
+use crate::model::cat_tag_outcome::CatTagOutcome;
+use crate::model::cat_value::CatValue;
+use crate::model::cat_visibility::CatVisibility;
 use crate::cat_service_fs::CatServiceFS;
 
 #[derive(Clone)]
@@ -19,55 +30,290 @@ impl CatSingleServiceFS {
     ///
     /// Loads and returns a cat_list object for data storage specified in the parameter graph_name.
     ///
-    pub fn get_all_cats(space_id: String, graph_name: String) -> CatList {
+    pub fn get_all_cats(space_id: String, graph_name: String) -> Result<CatList, CatError> {
 
         CatServiceFS::new(space_id).get_all_cats(graph_name)
 
     }
 
-    pub fn get_all_cats_w_conditions(space_id: String, graph_name: String) -> CatList {
+    pub fn get_all_cats_w_conditions(space_id: String, graph_name: String) -> Result<CatList, CatError> {
 
         CatServiceFS::new(space_id).get_all_cats_w_conditions(graph_name)
 
     }
 
     ///
-    /// This is supposed to replace get_all_cat_elements and return a cat_element_list instead of a
-    /// HashMap.
+    /// Loads and returns the elements tagged with the cat cat_id, populated with the descriptor
+    /// notes of the tagged points.
     ///
-    pub fn get_cat_elements(space_id: String, graph_name: String, cat_id: String) -> CatElementList {
+    pub fn get_cat_elements(space_id: String, graph_name: String, cat_id: String) -> Result<CatElementList, CatError> {
 
         CatServiceFS::new(space_id).get_cat_elements(graph_name, cat_id)
     }
 
+    pub fn get_cats_for_point(space_id: String, graph_name: String, point: Point) -> Result<CatList, CatError> {
+
+        CatServiceFS::new(space_id).get_cats_for_point(graph_name, point)
+    }
+
+    pub fn get_cats_for_points(space_id: String, graph_name: String, points: Vec<Point>) -> Result<HashMap<Point, CatList>, CatError> {
+
+        CatServiceFS::new(space_id).get_cats_for_points(graph_name, points)
+    }
+
     ///
-    /// Returns a list of InfoEdge objects for a given cat graph_name referring to a specific
-    /// category type.
-    /// This means triples and their descriptor notes.
+    /// Returns the names of the elements tagged with the cat cat_id keyed by point.
     ///
-    pub fn get_all_cat_elements(space_id: String, graph_name: String, cat_id: String) -> HashMap<Point, NameOrPoint> {
+    #[deprecated(note = "use get_cat_elements and CatElementList::get_all_cat_element_names instead")]
+    pub fn get_all_cat_elements(space_id: String, graph_name: String, cat_id: String) -> Result<HashMap<Point, NameOrPoint>, CatError> {
     
+        #[allow(deprecated)]
         CatServiceFS::new(space_id).get_all_cat_elements(graph_name, cat_id)
     }
 
-   pub fn create_cat(space_id:String, cat_point: &str, cat_name: &str, cat_icon: &str, cat_desc: &str) {
+   pub fn create_cat(space_id:String, cat_point: &str, cat_name: &str, cat_icon: &str, cat_desc: &str) -> Result<Cat, CatError> {
 
         CatServiceFS::new(space_id).create_cat(cat_point, cat_name, cat_icon, cat_desc)
    }
 
-   pub fn delete_cat(space_id: String, cat: Cat) {
+   pub fn update_cat(space_id: String, cat: Cat, cat_name: &str, cat_icon: &str, cat_desc: &str) -> Result<Cat, CatError> {
+
+        CatServiceFS::new(space_id).update_cat(cat, cat_name, cat_icon, cat_desc)
+   }
+
+   pub fn get_cat_revisions(space_id: String, graph_name: String, cat: Cat) -> Result<Vec<Cat>, CatError> {
+
+        CatServiceFS::new(space_id).get_cat_revisions(graph_name, cat)
+   }
+
+   pub fn delete_cat(space_id: String, cat: Cat) -> Result<Cat, CatError> {
 
         CatServiceFS::new(space_id).delete_cat(cat)
             
    }
 
-    pub fn create_cat_element(space_id: String, cat: Cat, id_to_tag: String) {
+    pub fn get_canceled_cats(space_id: String, graph_name: String) -> Result<CatList, CatError> {
+
+        CatServiceFS::new(space_id).get_canceled_cats(graph_name)
+    }
+
+    pub fn restore_cat(space_id: String, cat: Cat) -> Result<Cat, CatError> {
+
+        CatServiceFS::new(space_id).restore_cat(cat)
+    }
+
+    pub fn create_cat_element(space_id: String, cat: Cat, id_to_tag: String) -> Result<CatElement, CatError> {
         CatServiceFS::new(space_id).create_cat_element(cat, id_to_tag)
     } 
+
+    pub fn remove_cat_element(space_id: String, cat_element: &CatElement) -> Result<CatElement, CatError> {
+        CatServiceFS::new(space_id).remove_cat_element(cat_element)
+    }
+
+    pub fn remove_cat_elements(space_id: String, cat_elements: CatElementList) -> Result<CatElementList, CatError> {
+        CatServiceFS::new(space_id).remove_cat_elements(cat_elements)
+    }
+
+    pub fn create_cat_parent(space_id: String, parent: Cat, child: Cat) -> Result<CatParent, CatError> {
+        CatServiceFS::new(space_id).create_cat_parent(parent, child)
+    }
+
+    pub fn remove_cat_parent(space_id: String, cat_parent: &CatParent) -> Result<CatParent, CatError> {
+        CatServiceFS::new(space_id).remove_cat_parent(cat_parent)
+    }
+
+    pub fn get_cat_parents(space_id: String, graph_name: String, cat: Cat) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).get_cat_parents(graph_name, cat)
+    }
+
+    pub fn get_cat_children(space_id: String, graph_name: String, cat: Cat) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).get_cat_children(graph_name, cat)
+    }
+
+    pub fn get_cat_ancestors(space_id: String, graph_name: String, cat: Cat) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).get_cat_ancestors(graph_name, cat)
+    }
+
+    pub fn get_cat_descendants(space_id: String, graph_name: String, cat: Cat) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).get_cat_descendants(graph_name, cat)
+    }
+
+    pub fn get_cat_elements_w_descendants(space_id: String, graph_name: String, cat_id: String) -> Result<CatElementList, CatError> {
+        CatServiceFS::new(space_id).get_cat_elements_w_descendants(graph_name, cat_id)
+    }
+
+    pub fn create_cat_condi(space_id: String, super_cat_id: String, sub_cat_id: String) -> Result<CatCondition, CatError> {
+        CatServiceFS::new(space_id).create_cat_condi(super_cat_id, sub_cat_id)
+    }
+
+    pub fn create_cat_condi_expr(space_id: String, condition: CatExpr, sub_cat_id: String) -> Result<CatCondition, CatError> {
+        CatServiceFS::new(space_id).create_cat_condi_expr(condition, sub_cat_id)
+    }
+
+    pub fn visible_cats_for_point(space_id: String, graph_name: String, point: Point) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).visible_cats_for_point(graph_name, point)
+    }
+
+    pub fn explain_cats_for_point(space_id: String, graph_name: String, point: Point) -> Result<Vec<CatVisibility>, CatError> {
+        CatServiceFS::new(space_id).explain_cats_for_point(graph_name, point)
+    }
+
+    pub fn validate_conditions(space_id: String, graph_name: String) -> Result<Vec<CatConditionIssue>, CatError> {
+        CatServiceFS::new(space_id).validate_conditions(graph_name)
+    }
+
+    pub fn get_cat_condis(space_id: String, graph_name: String, sub_cat_id: String) -> Result<Vec<CatCondition>, CatError> {
+        CatServiceFS::new(space_id).get_cat_condis(graph_name, sub_cat_id)
+    }
+
+    pub fn remove_cat_condi(space_id: String, cat_condition: &CatCondition) -> Result<CatCondition, CatError> {
+        CatServiceFS::new(space_id).remove_cat_condi(cat_condition)
+    }
+
+    pub fn replace_cat_condi(space_id: String, cat_condition: &CatCondition, condition: CatExpr) -> Result<CatCondition, CatError> {
+        CatServiceFS::new(space_id).replace_cat_condi(cat_condition, condition)
+    }
+
+    pub fn clear_cat_condis(space_id: String, sub_cat_id: String) -> Result<Vec<CatCondition>, CatError> {
+        CatServiceFS::new(space_id).clear_cat_condis(sub_cat_id)
+    }
+
+    pub fn create_cat_facet(space_id: String, facet_point: &str, facet_name: &str, facet_desc: &str) -> Result<CatFacet, CatError> {
+        CatServiceFS::new(space_id).create_cat_facet(facet_point, facet_name, facet_desc)
+    }
+
+    pub fn delete_cat_facet(space_id: String, facet: CatFacet) -> Result<CatFacet, CatError> {
+        CatServiceFS::new(space_id).delete_cat_facet(facet)
+    }
+
+    pub fn get_all_cat_facets(space_id: String, graph_name: String) -> Result<Vec<CatFacet>, CatError> {
+        CatServiceFS::new(space_id).get_all_cat_facets(graph_name)
+    }
+
+    pub fn add_cat_to_facet(space_id: String, facet: CatFacet, cat: Cat) -> Result<CatFacet, CatError> {
+        CatServiceFS::new(space_id).add_cat_to_facet(facet, cat)
+    }
+
+    pub fn remove_cat_from_facet(space_id: String, facet: CatFacet, cat: Cat) -> Result<CatFacet, CatError> {
+        CatServiceFS::new(space_id).remove_cat_from_facet(facet, cat)
+    }
+
+    pub fn get_cat_facet_values(space_id: String, graph_name: String, facet: &CatFacet) -> Result<HashMap<Point, Cat>, CatError> {
+        CatServiceFS::new(space_id).get_cat_facet_values(graph_name, facet)
+    }
+
+    pub fn set_cat_facet_order(space_id: String, facet: CatFacet, cats: Vec<Cat>) -> Result<CatFacet, CatError> {
+        CatServiceFS::new(space_id).set_cat_facet_order(facet, cats)
+    }
+
+    pub fn get_cat_elements_in_range(space_id: String, graph_name: String, facet: &CatFacet, min: Option<Cat>, max: Option<Cat>) -> Result<CatElementList, CatError> {
+        CatServiceFS::new(space_id).get_cat_elements_in_range(graph_name, facet, min, max)
+    }
+
+    pub fn move_cat_before(space_id: String, cat: Cat, other: Cat) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).move_cat_before(cat, other)
+    }
+
+    pub fn move_cat_after(space_id: String, cat: Cat, other: Cat) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).move_cat_after(cat, other)
+    }
+
+    pub fn move_cat_to_index(space_id: String, cat: Cat, index: usize) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).move_cat_to_index(cat, index)
+    }
+
+    pub fn set_cat_property(space_id: String, cat: Cat, key: &str, value: CatValue) -> Result<Cat, CatError> {
+        CatServiceFS::new(space_id).set_cat_property(cat, key, value)
+    }
+
+    pub fn remove_cat_property(space_id: String, cat: Cat, key: &str) -> Result<Cat, CatError> {
+        CatServiceFS::new(space_id).remove_cat_property(cat, key)
+    }
+
+    pub fn get_cat_property(space_id: String, graph_name: String, cat: &Cat, key: &str) -> Result<Option<CatValue>, CatError> {
+        CatServiceFS::new(space_id).get_cat_property(graph_name, cat, key)
+    }
+
+    pub fn get_cat_properties(space_id: String, graph_name: String, cat: &Cat) -> Result<HashMap<String, CatValue>, CatError> {
+        CatServiceFS::new(space_id).get_cat_properties(graph_name, cat)
+    }
+
+    pub fn get_all_cats_w_properties(space_id: String, graph_name: String) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).get_all_cats_w_properties(graph_name)
+    }
+
+    pub fn create_cat_element_w_value(space_id: String, cat: Cat, id_to_tag: String, value: CatValue) -> Result<CatElement, CatError> {
+        CatServiceFS::new(space_id).create_cat_element_w_value(cat, id_to_tag, value)
+    }
+
+    pub fn set_cat_element_value(space_id: String, cat_element: &CatElement, value: CatValue) -> Result<CatElement, CatError> {
+        CatServiceFS::new(space_id).set_cat_element_value(cat_element, value)
+    }
+
+    pub fn remove_cat_element_value(space_id: String, cat_element: &CatElement) -> Result<CatElement, CatError> {
+        CatServiceFS::new(space_id).remove_cat_element_value(cat_element)
+    }
+
+    pub fn get_cat_elements_w_values(space_id: String, graph_name: String, cat_id: String, min: Option<CatValue>, max: Option<CatValue>, descending: bool) -> Result<CatElementList, CatError> {
+        CatServiceFS::new(space_id).get_cat_elements_w_values(graph_name, cat_id, min, max, descending)
+    }
+
+    pub fn get_stamp(space_id: String, graph_name: String, triple_id: String) -> Result<Option<CatStamp>, CatError> {
+        CatServiceFS::new(space_id).get_stamp(graph_name, triple_id)
+    }
+
+    pub fn get_cancellation_stamp(space_id: String, graph_name: String, triple_id: String) -> Result<Option<CatStamp>, CatError> {
+        CatServiceFS::new(space_id).get_cancellation_stamp(graph_name, triple_id)
+    }
+
+    pub fn get_all_cats_w_stamps(space_id: String, graph_name: String) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).get_all_cats_w_stamps(graph_name)
+    }
+
+    pub fn get_cat_elements_w_stamps(space_id: String, graph_name: String, cat_id: String, since: Option<u64>) -> Result<CatElementList, CatError> {
+        CatServiceFS::new(space_id).get_cat_elements_w_stamps(graph_name, cat_id, since)
+    }
+
+    pub fn get_cat_history(space_id: String, graph_name: String, cat: &Cat) -> Result<Vec<CatEvent>, CatError> {
+        CatServiceFS::new(space_id).get_cat_history(graph_name, cat)
+    }
+
+    pub fn get_all_cats_as_of(space_id: String, graph_name: String, timestamp: u64) -> Result<CatList, CatError> {
+        CatServiceFS::new(space_id).get_all_cats_as_of(graph_name, timestamp)
+    }
+
+    pub fn get_cat_elements_as_of(space_id: String, graph_name: String, cat_id: String, timestamp: u64) -> Result<CatElementList, CatError> {
+        CatServiceFS::new(space_id).get_cat_elements_as_of(graph_name, cat_id, timestamp)
+    }
+
+    pub fn merge_cats(space_id: String, source: Cat, target: Cat) -> Result<Cat, CatError> {
+        CatServiceFS::new(space_id).merge_cats(source, target)
+    }
+
+    pub fn get_merge_target(space_id: String, graph_name: String, cat_id: String) -> Result<Option<Cat>, CatError> {
+        CatServiceFS::new(space_id).get_merge_target(graph_name, cat_id)
+    }
+
+    pub fn split_cat<F>(space_id: String, cat: Cat, new_cats: Vec<Cat>, keep_as_parent: bool, assign: F) -> Result<CatList, CatError> where F: FnMut(&CatElement) -> Option<usize> {
+        CatServiceFS::new(space_id).split_cat(cat, new_cats, keep_as_parent, assign)
+    }
+
+    pub fn tag_many(space_id: String, pairs: Vec<(Cat, Point)>) -> Result<Vec<CatTagOutcome>, CatError> {
+        CatServiceFS::new(space_id).tag_many(pairs)
+    }
+
+    pub fn untag_many(space_id: String, pairs: Vec<(Cat, Point)>) -> Result<Vec<CatTagOutcome>, CatError> {
+        CatServiceFS::new(space_id).untag_many(pairs)
+    }
+
+    pub fn query_cat_elements(space_id: String, graph_name: String, query: &CatExpr) -> Result<CatElementList, CatError> {
+        CatServiceFS::new(space_id).query_cat_elements(graph_name, query)
+    }
 }
 
 #[test]
 fn populate_cat_list_test() {
+    use infograph::{InfoTriple, Descriptor, InfoTable};
 
     let cat_id= "5fe9374c5e9e27ebb78f8bf7cd78bbb23ee51e672dc54c603ec1c5b3eef33feb".to_string();
     let mut info_table: InfoTable = InfoTable::new();
@@ -118,7 +364,7 @@ fn populate_cat_list_test() {
     let _ = info_table.add_triple(it3);
 
     let cat_service = CatServiceFS::new("test".to_string());
-    let cats: CatList = cat_service.populate_cat_list(info_table, descs);
+    let cats: CatList = cat_service.populate_cat_list(info_table, descs).unwrap();
     assert!(!cats.cats.is_empty());
 
     let mut it = cats.into_iter();
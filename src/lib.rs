@@ -3,5 +3,22 @@ pub use model::cat;
 pub use model::cat_list;
 pub use self::model::cat_element;
 pub use self::model::cat_element_list;
+pub use self::model::cat_event;
+pub use self::model::cat_condition;
+pub use self::model::cat_condition_issue;
+pub use self::model::cat_expr;
+pub use self::model::cat_facet;
+pub use self::model::cat_parent;
+pub use self::model::cat_stamp;
+pub use self::model::cat_tag_outcome;
+pub use self::model::cat_value;
+pub use self::model::cat_visibility;
+pub mod cat_error;
+pub mod store;
+pub use store::cat_store;
+pub use store::cat_store_fs;
+pub use store::cat_store_mem;
+pub mod cat_service;
 pub mod cat_service_fs;
+pub mod cat_service_mem;
 pub mod cat_single_service_fs;
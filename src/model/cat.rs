@@ -1,7 +1,12 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
 use infograph::types::Point;
 
+use crate::model::cat_expr::CatExpr;
+use crate::model::cat_stamp::CatStamp;
+use crate::model::cat_value::CatValue;
+
 ///
 /// This struct represents a category.
 /// The idea is that the library users can create their own categories for tagging stuff.
@@ -56,9 +61,29 @@ pub struct Cat {
     pub description: String,
 
     ///
-    /// Only show the category for elements already tagged with an element from this list.
+    /// Only show the category for elements whose categories satisfy this expression. None if the
+    /// category is always shown. Separate conditions created for the same category are combined
+    /// with CatExpr::Or.
+    ///
+    pub condition: Option<CatExpr>,
+
+    ///
+    /// The place of the category in the user defined display order, starting at 0. None if the
+    /// category has not been placed yet. See CatList for how categories are ordered.
+    ///
+    pub position: Option<usize>,
+
+    ///
+    /// Further properties of the category like a colour or a keyboard shortcut, keyed by name.
+    /// Only loaded by the service methods saying so, empty otherwise.
+    ///
+    pub properties: HashMap<String, CatValue>,
+
+    ///
+    /// Who created the category, or restored it last, and when. Only loaded by the service methods
+    /// saying so, None otherwise.
     ///
-    pub conditioned_list: Vec<String>,
+    pub stamp: Option<CatStamp>,
 }
 
 impl Default for Cat {
@@ -78,12 +103,15 @@ impl Cat {
                 name: "".to_string(), 
                 icon_uri: "".to_string(), 
                 description: "".to_string(), 
-                conditioned_list: Vec::new(),
+                condition: None,
+                position: None,
+                properties: HashMap::new(),
+                stamp: None,
         }
     }
 
     pub fn has_conditions(&self) -> bool {
-        !self.conditioned_list.is_empty()
+        self.condition.is_some()
     }
 }
 
@@ -95,7 +123,7 @@ impl Ord for Cat {
 
 impl PartialOrd for Cat {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.point.cmp(&other.point))
+        Some(self.cmp(other))
     }
 }
 
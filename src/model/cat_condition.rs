@@ -0,0 +1,45 @@
+use crate::model::cat_expr::CatExpr;
+use crate::model::cat_stamp::CatStamp;
+
+///
+/// This struct represents a single condition of a category, meaning that the sub category is only
+/// to be shown for elements whose categories satisfy the condition expression.
+/// Behind the scenes a condition is modeled using two info triples. The first one decorates the sub
+/// category's triple as conditioned, the second one pairs that decoration with the super category,
+/// or with the root node of the expression if the condition is more than a single category.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatCondition {
+
+    ///
+    /// The triple ID of the triple pairing the conditioned decoration with the super category.
+    ///
+    pub triple_id: String,
+
+    ///
+    /// The triple ID of the triple decorating the sub category as conditioned.
+    ///
+    pub conditioned_id: String,
+
+    ///
+    /// The ID paired with the conditioned decoration. This is the triple ID of the category that has
+    /// to be present for a condition on a single category, the ID of the expression's root node
+    /// otherwise.
+    ///
+    pub super_cat_id: String,
+
+    ///
+    /// The triple ID of the category being conditioned.
+    ///
+    pub sub_cat_id: String,
+
+    ///
+    /// The expression that has to be satisfied for the sub category to be shown.
+    ///
+    pub condition: CatExpr,
+
+    ///
+    /// Who created the condition and when. None if the condition was written without a stamp.
+    ///
+    pub stamp: Option<CatStamp>,
+}
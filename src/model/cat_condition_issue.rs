@@ -0,0 +1,25 @@
+///
+/// A problem with the conditions of a space as found by CatService::validate_conditions.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CatConditionIssue {
+
+    ///
+    /// The condition of the category sub_cat_id refers to the category cat_id, which has been
+    /// deleted (canceled).
+    ///
+    CanceledCat { sub_cat_id: String, cat_id: String },
+
+    ///
+    /// The condition of the category sub_cat_id refers to cat_id, which has never been created as a
+    /// category.
+    ///
+    UnknownCat { sub_cat_id: String, cat_id: String },
+
+    ///
+    /// The categories are conditioned on each other in a circle, so none of them can ever be shown
+    /// through the others. Holds the triple IDs of the categories involved, starting and ending
+    /// with the same one.
+    ///
+    Cycle(Vec<String>),
+}
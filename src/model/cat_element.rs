@@ -1,9 +1,13 @@
 use std::cmp::Ordering;
-use std::{error::Error, fmt};
+use std::fmt;
 
 use infograph::types::Point; 
 use infograph::{InfoTriple, Descriptor};
 
+use crate::cat_error::CatError;
+use crate::model::cat_stamp::CatStamp;
+use crate::model::cat_value::CatValue;
+
 #[derive(Clone, Debug, Eq)]
 pub struct CatElement {
     ///
@@ -45,6 +49,18 @@ pub struct CatElement {
     ///
     pub desc_id: String,
 
+    ///
+    /// An optional value recorded with the tag, like a confidence score or an estimate. Only
+    /// loaded by the service methods saying so, None otherwise.
+    ///
+    pub value: Option<CatValue>,
+
+    ///
+    /// Who tagged the point with the category and when. If the point has been tagged more than
+    /// once this is the last time. Only loaded by the service methods saying so, None otherwise.
+    ///
+    pub stamp: Option<CatStamp>,
+
 }
 
 impl fmt::Display for CatElement {
@@ -53,29 +69,31 @@ impl fmt::Display for CatElement {
     }
 }
 
-impl Error for CatElement {}
-
 impl CatElement {
 
-    fn add_desc(&mut self, desc: Descriptor) {
+    pub(crate) fn add_desc(&mut self, desc: Descriptor) {
         self.name = desc.name;
         self.label = desc.label;
         self.description = desc.description;
         self.desc_id = desc.desc_id;
     }
 
-    pub fn from_triple(cat_id: String, value: InfoTriple) -> Result<CatElement, Box<dyn Error>> {
-        let other_half = value.clone().other_half(cat_id.clone()); 
-        if other_half.is_err() {
-            return Err("Not a CatElement".to_string().into())
-        } else {
-            return Ok(CatElement { cat_id: cat_id.clone(), element_id: value.id.clone(), 
-                point: other_half.unwrap(), 
+    ///
+    /// Creates a CatElement from a triple pairing cat_id with the tagged point.
+    /// Fails with CatError::MalformedTriple if the triple is not paired with cat_id.
+    ///
+    pub fn from_triple(cat_id: String, value: InfoTriple) -> Result<CatElement, CatError> {
+        match value.other_half(cat_id.clone()) {
+            Err(reason) => Err(CatError::MalformedTriple(reason)),
+            Ok(point) => Ok(CatElement { cat_id: cat_id.clone(), element_id: value.id.clone(), 
+                point, 
                 name: "".to_string(), 
                 label: "".to_string(), 
                 description: "".to_string(), 
-                desc_id: "".to_string() 
-            })    
+                desc_id: "".to_string(),
+                value: None,
+                stamp: None,
+            }),
         }
     }
 
@@ -91,7 +109,7 @@ impl Ord for CatElement {
 
 impl PartialOrd for CatElement {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.point.cmp(&other.point))
+        Some(self.cmp(other))
     }
 }
 
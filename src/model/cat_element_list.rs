@@ -1,5 +1,5 @@
 
-use std::{collections::HashMap, vec::IntoIter};
+use std::{cmp::Ordering, collections::HashMap, vec::IntoIter};
 
 use infograph::types::{NameOrPoint, Point};
 
@@ -8,6 +8,11 @@ use crate::model::cat_element::CatElement;
 #[derive(Clone,Debug)]
 pub struct CatElementList {
     cat_elements: Vec<CatElement>,
+
+    ///
+    /// True once the list has been sorted explicitly, the order is kept when iterating then.
+    ///
+    sorted: bool,
 }
 
 impl IntoIterator for CatElementList {
@@ -17,7 +22,9 @@ impl IntoIterator for CatElementList {
     fn into_iter(self) -> IntoIter<CatElement> {
     
         let mut vec: Vec<CatElement> = self.cat_elements.to_vec();
-        vec.sort();
+        if !self.sorted {
+            vec.sort();
+        }
         vec.into_iter()
     }
 }
@@ -31,7 +38,7 @@ impl Default for CatElementList {
 impl CatElementList {
 
     pub fn new() -> Self{
-        CatElementList {cat_elements: Vec::new()}
+        CatElementList {cat_elements: Vec::new(), sorted: false}
     }
 
     ///
@@ -64,6 +71,27 @@ impl CatElementList {
         self.cat_elements.push(cat_element);
     }
 
+    ///
+    /// Sorts the list with compare, elements comparing equal are ordered by point. Iterating the
+    /// list keeps this order instead of ordering by point, elements added afterwards come last.
+    ///
+    pub fn sort_by<F>(&mut self, mut compare: F) where F: FnMut(&CatElement, &CatElement) -> Ordering {
+        self.cat_elements.sort_by(|a, b|compare(a, b).then_with(||a.cmp(b)));
+        self.sorted = true;
+    }
+
+    ///
+    /// Sorts the list by the position of each element's cat in cat_ids, which holds cat points like
+    /// CatElement::cat_id. Elements of cats not in cat_ids come last.
+    ///
+    pub fn sort_by_ordinal(&mut self, cat_ids: &[Point]) {
+        let position = |cat_element: &CatElement| cat_ids
+            .iter()
+            .position(|cat_id|*cat_id == cat_element.cat_id)
+            .unwrap_or(cat_ids.len());
+        self.sort_by(|a, b|position(a).cmp(&position(b)));
+    }
+
 }
 
 #[test]
@@ -77,6 +105,8 @@ fn get_all_cat_element_names_test() {
      name: "name1".to_string(),
      label: "label1".to_string(),
      description: "button no. 1".to_string(),
+     value: None,
+     stamp: None,
     };
     let ce2 = CatElement {
      element_id: "6".to_string(),
@@ -86,6 +116,8 @@ fn get_all_cat_element_names_test() {
      name: "".to_string(),
      label: "label2".to_string(),
      description: "button no. 2".to_string(),
+     value: None,
+     stamp: None,
     };
     let ce3 = CatElement {
      element_id: "9".to_string(),
@@ -95,6 +127,8 @@ fn get_all_cat_element_names_test() {
      name: "name3".to_string(),
      label: "label3".to_string(),
      description: "button no. 3".to_string(),
+     value: None,
+     stamp: None,
     };
 
     let mut cat_elements: CatElementList = CatElementList::new();
@@ -115,3 +149,29 @@ fn get_all_cat_element_names_test() {
     assert!(it.next().is_none());
 }
 
+#[test]
+fn sort_by_ordinal_test() {
+    let cat_element = |point: &str, cat_id: &str| CatElement {
+     element_id: format!("{}-{}", cat_id, point),
+     point: point.to_string(),
+     cat_id: cat_id.to_string(),
+     desc_id: "".to_string(),
+     name: "".to_string(),
+     label: "".to_string(),
+     description: "".to_string(),
+     value: None,
+     stamp: None,
+    };
+
+    let mut cat_elements = CatElementList::new();
+    cat_elements.add(cat_element("a", "low"));
+    cat_elements.add(cat_element("b", "high"));
+    cat_elements.add(cat_element("c", "other"));
+    cat_elements.add(cat_element("d", "medium"));
+    cat_elements.add(cat_element("e", "high"));
+    let points = |cat_elements: CatElementList| -> Vec<String> { cat_elements.into_iter().map(|ce|ce.point).collect() };
+    assert_eq!(points(cat_elements.clone()), vec!["a", "b", "c", "d", "e"]);
+
+    cat_elements.sort_by_ordinal(&["high".to_string(), "medium".to_string(), "low".to_string()]);
+    assert_eq!(points(cat_elements), vec!["b", "e", "d", "a", "c"]);
+}
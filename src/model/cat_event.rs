@@ -0,0 +1,82 @@
+use infograph::types::Point;
+
+use crate::model::cat_expr::CatExpr;
+use crate::model::cat_stamp::CatStamp;
+
+///
+/// This struct represents a change of a category found in the history of its space, like the
+/// category being renamed or a point being tagged with it.
+/// Behind the scenes every change is an info triple appended to the info table, as nothing is ever
+/// removed from it.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatEvent {
+
+    ///
+    /// What happened.
+    ///
+    pub kind: CatEventKind,
+
+    ///
+    /// The triple ID of the info triple recording the change.
+    ///
+    pub triple_id: String,
+
+    ///
+    /// Who made the change and when. None for changes written without a stamp.
+    ///
+    pub stamp: Option<CatStamp>,
+}
+
+///
+/// The kinds of changes a category goes through.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CatEventKind {
+
+    ///
+    /// The category was created.
+    ///
+    Created,
+
+    ///
+    /// The category was given a new revision, holding its name from then on. Changes of the icon
+    /// or the description are revisions as well.
+    ///
+    Renamed(String),
+
+    ///
+    /// The category was deleted.
+    ///
+    Canceled,
+
+    ///
+    /// The deleted category was restored.
+    ///
+    Restored,
+
+    ///
+    /// The category was merged into the category with the triple ID held.
+    ///
+    Merged(String),
+
+    ///
+    /// The category was given a condition, holding the expression.
+    ///
+    Conditioned(CatExpr),
+
+    ///
+    /// A condition of the category was removed, holding its expression.
+    ///
+    Unconditioned(CatExpr),
+
+    ///
+    /// The point held was tagged with the category.
+    ///
+    Tagged(Point),
+
+    ///
+    /// The tag of the point held was removed.
+    ///
+    Untagged(Point),
+}
@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+///
+/// A boolean expression over categories, used as the condition under which a category is shown.
+/// The leaves are the triple IDs of categories, which count as true when the element in question is
+/// tagged with that category.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CatExpr {
+
+    ///
+    /// True if the category with this triple ID is present.
+    ///
+    Cat(String),
+
+    ///
+    /// True if all of the expressions are true. Must not be empty.
+    ///
+    And(Vec<CatExpr>),
+
+    ///
+    /// True if at least one of the expressions is true. Must not be empty.
+    ///
+    Or(Vec<CatExpr>),
+
+    ///
+    /// True if the expression is false.
+    ///
+    Not(Box<CatExpr>),
+}
+
+impl CatExpr {
+
+    ///
+    /// Evaluates the expression given the triple IDs of the categories present.
+    ///
+    pub fn eval(&self, cat_ids: &HashSet<String>) -> bool {
+        match self {
+            CatExpr::Cat(cat_id) => cat_ids.contains(cat_id),
+            CatExpr::And(exprs) => exprs.iter().all(|expr|expr.eval(cat_ids)),
+            CatExpr::Or(exprs) => exprs.iter().any(|expr|expr.eval(cat_ids)),
+            CatExpr::Not(expr) => !expr.eval(cat_ids),
+        }
+    }
+
+    ///
+    /// Returns the part of the expression that is not satisfied given the triple IDs of the
+    /// categories present, or None if the expression is true. For a false And these are only the
+    /// operands that are false, a false Or, Not or Cat is returned as it is.
+    ///
+    pub fn unmet(&self, cat_ids: &HashSet<String>) -> Option<CatExpr> {
+        if self.eval(cat_ids) {
+            return None;
+        }
+        match self {
+            CatExpr::And(exprs) => {
+                let mut unmet: Vec<CatExpr> = exprs.iter().filter_map(|expr|expr.unmet(cat_ids)).collect();
+                if unmet.len() == 1 { unmet.pop() } else { Some(CatExpr::And(unmet)) }
+            },
+            _ => Some(self.clone()),
+        }
+    }
+
+    ///
+    /// Returns the triple IDs of all categories the expression refers to, in the order they appear.
+    ///
+    pub fn cat_ids(&self) -> Vec<String> {
+        match self {
+            CatExpr::Cat(cat_id) => vec![cat_id.clone()],
+            CatExpr::And(exprs) | CatExpr::Or(exprs) => exprs.iter().flat_map(|expr|expr.cat_ids()).collect(),
+            CatExpr::Not(expr) => expr.cat_ids(),
+        }
+    }
+
+    ///
+    /// Returns the expression with every reference to the category from replaced by a reference to
+    /// the category to, both given as triple IDs.
+    ///
+    pub fn replace_cat(&self, from: &str, to: &str) -> CatExpr {
+        match self {
+            CatExpr::Cat(cat_id) if cat_id == from => CatExpr::Cat(to.to_string()),
+            CatExpr::Cat(..) => self.clone(),
+            CatExpr::And(exprs) => CatExpr::And(exprs.iter().map(|expr|expr.replace_cat(from, to)).collect()),
+            CatExpr::Or(exprs) => CatExpr::Or(exprs.iter().map(|expr|expr.replace_cat(from, to)).collect()),
+            CatExpr::Not(expr) => CatExpr::Not(Box::new(expr.replace_cat(from, to))),
+        }
+    }
+}
+
+#[test]
+fn eval_test() {
+
+    let present: HashSet<String> = ["a".to_string(), "b".to_string()].into_iter().collect();
+    let cat = |id: &str| CatExpr::Cat(id.to_string());
+
+    assert!(cat("a").eval(&present));
+    assert!(!cat("c").eval(&present));
+    assert!(CatExpr::And(vec![cat("a"), cat("b")]).eval(&present));
+    assert!(!CatExpr::And(vec![cat("a"), cat("c")]).eval(&present));
+    assert!(CatExpr::Or(vec![cat("c"), cat("b")]).eval(&present));
+    assert!(CatExpr::Not(Box::new(cat("c"))).eval(&present));
+
+    let nested = CatExpr::And(vec![cat("a"), CatExpr::Not(Box::new(CatExpr::Or(vec![cat("c"), cat("d")])))]);
+    assert!(nested.eval(&present));
+    assert_eq!(nested.cat_ids(), vec!["a", "c", "d"]);
+
+    assert_eq!(nested.unmet(&present), None);
+    let unmet = CatExpr::And(vec![cat("a"), cat("c"), CatExpr::Not(Box::new(cat("b")))]).unmet(&present);
+    assert_eq!(unmet, Some(CatExpr::And(vec![cat("c"), CatExpr::Not(Box::new(cat("b")))])));
+    assert_eq!(CatExpr::And(vec![cat("a"), cat("c")]).unmet(&present), Some(cat("c")));
+
+    let replaced = CatExpr::And(vec![cat("a"), CatExpr::Not(Box::new(CatExpr::Or(vec![cat("c"), cat("a")])))]);
+    assert_eq!(nested.replace_cat("d", "a"), replaced);
+}
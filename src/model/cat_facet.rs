@@ -0,0 +1,48 @@
+///
+/// This struct represents a facet, a group of mutually exclusive categories like a status with the
+/// categories Todo, Doing and Done. An element can only be tagged with one category of a facet at a
+/// time.
+/// Behind the scenes a facet is modeled like a category, using one descriptor note and one info
+/// triple pairing its point with the facet tag. Each member category's triple is paired with the
+/// facet's triple.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatFacet {
+
+    ///
+    /// The triple ID of the facet triple. Canceling this triple deletes the facet.
+    ///
+    pub triple_id: String,
+
+    ///
+    /// The point of the facet triple and of its descriptor note.
+    ///
+    pub point: String,
+
+    ///
+    /// The ID of the descriptor note.
+    ///
+    pub desc_id: String,
+
+    ///
+    /// The name of the facet and the name field in its descriptor note.
+    ///
+    pub name: String,
+
+    ///
+    /// A description of the facet using the description field of the descriptor note.
+    ///
+    pub description: String,
+
+    ///
+    /// The triple IDs of the member categories, in the order they were added unless the facet is
+    /// ordinal.
+    ///
+    pub cat_ids: Vec<String>,
+
+    ///
+    /// True if an explicit order of the member categories has been declared, see
+    /// CatService::set_cat_facet_order. cat_ids is in that order then, lowest first.
+    ///
+    pub ordinal: bool,
+}
@@ -4,6 +4,11 @@ use infograph::types::{NameOrPoint, Point};
 
 use crate::model::cat::Cat;
 
+///
+/// A list of categories keyed by point. Iterating the list yields the categories in display order:
+/// first the categories with a position, by position, then the others by name, using the point for
+/// categories without a name.
+///
 #[derive(Clone, Debug)]
 pub struct CatList {
     pub cats: HashMap<Point,Cat>,
@@ -19,11 +24,19 @@ impl IntoIterator for CatList {
             .values()
             .cloned()
             .collect();
-        vec.sort();
+        vec.sort_by(|a, b|display_key(a).cmp(&display_key(b)));
         vec.into_iter()
     }
 }
 
+///
+/// The key categories are sorted by when iterating a CatList.
+///
+fn display_key(cat: &Cat) -> (bool, Option<usize>, String, &Point) {
+    let name = if cat.name.is_empty() { &cat.point } else { &cat.name };
+    (cat.position.is_none(), cat.position, name.to_lowercase(), &cat.point)
+}
+
 impl Default for CatList {
     fn default() -> Self {
         CatList::new()
@@ -47,6 +60,17 @@ impl CatList {
             .cloned()
     }
 
+    ///
+    /// Like get but finds the cat by its triple_id rather than its point.
+    ///
+    pub fn get_by_triple_id(&self, triple_id: String) -> Option<Cat> {
+        self
+            .cats
+            .values()
+            .find(|cat|cat.triple_id == triple_id)
+            .cloned()
+    }
+
     ///
     /// Returns a list of InfoEdge objects for a given cat graph_name referring to the "is category" tag.
     /// This means triples and their descriptor notes.
@@ -82,7 +106,10 @@ fn get_all_cat_names_test() {
      name: "name1".to_string(),
      icon_uri: "uri1".to_string(),
      description: "button no. 1".to_string(),
-     conditioned_list: Vec::new(),
+     condition: None,
+     position: None,
+     properties: HashMap::new(),
+     stamp: None,
     };
     let cat2 = Cat {
      triple_id: "6".to_string(),
@@ -92,7 +119,10 @@ fn get_all_cat_names_test() {
      name: "".to_string(),
      icon_uri: "uri2".to_string(),
      description: "button no. 2".to_string(),
-     conditioned_list: Vec::new(),
+     condition: None,
+     position: None,
+     properties: HashMap::new(),
+     stamp: None,
     };
     let cat3 = Cat {
      triple_id: "9".to_string(),
@@ -102,7 +132,10 @@ fn get_all_cat_names_test() {
      name: "name3".to_string(),
      icon_uri: "uri3".to_string(),
      description: "button no. 3".to_string(),
-     conditioned_list: Vec::new(),
+     condition: None,
+     position: None,
+     properties: HashMap::new(),
+     stamp: None,
     };
 
     let mut cats: CatList = CatList::new();
@@ -133,7 +166,10 @@ fn into_iter_test() {
      name: "name1".to_string(),
      icon_uri: "uri1".to_string(),
      description: "button no. 1".to_string(),
-     conditioned_list: Vec::new(),
+     condition: None,
+     position: None,
+     properties: HashMap::new(),
+     stamp: None,
     };
     let cat2 = Cat {
      triple_id: "6".to_string(),
@@ -143,7 +179,10 @@ fn into_iter_test() {
      name: "".to_string(),
      icon_uri: "uri2".to_string(),
      description: "button no. 2".to_string(),
-     conditioned_list: Vec::new(),
+     condition: None,
+     position: None,
+     properties: HashMap::new(),
+     stamp: None,
     };
     let cat3 = Cat {
      triple_id: "9".to_string(),
@@ -153,7 +192,10 @@ fn into_iter_test() {
      name: "name3".to_string(),
      icon_uri: "uri3".to_string(),
      description: "button no. 3".to_string(),
-     conditioned_list: Vec::new(),
+     condition: None,
+     position: None,
+     properties: HashMap::new(),
+     stamp: None,
     };
 
     let mut cats: CatList = CatList::new();
@@ -165,10 +207,23 @@ fn into_iter_test() {
         println!("this is just to prove the for loop works cat:{:?}", cat);
     }    
     
+    // without positions by name, cat2 has no name and goes by its point
     let mut iter = cats.clone().into_iter();
-    assert_eq!(cat1.point,iter.next().unwrap().point);
     assert_eq!(cat2.point,iter.next().unwrap().point);
+    assert_eq!(cat1.point,iter.next().unwrap().point);
+    assert_eq!(cat3.point,iter.next().unwrap().point);
+    assert!(iter.next().is_none());   
+
+    let mut cat3 = cat3;
+    cat3.position = Some(0);
+    cats.add(cat3.clone());
+    let mut cat1 = cat1;
+    cat1.position = Some(1);
+    cats.add(cat1.clone());
+    let mut iter = cats.clone().into_iter();
     assert_eq!(cat3.point,iter.next().unwrap().point);
+    assert_eq!(cat1.point,iter.next().unwrap().point);
+    assert_eq!(cat2.point,iter.next().unwrap().point);
     assert!(iter.next().is_none());   
 }
 
@@ -0,0 +1,31 @@
+///
+/// This struct represents a parent/child relation between two categories, meaning that the child
+/// is a sub-category of the parent.
+/// Behind the scenes the relation is modeled using two info triples. The first one decorates the
+/// child category's triple as having a parent, the second one pairs that decoration with the parent
+/// category's triple.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatParent {
+
+    ///
+    /// The triple ID of the triple pairing the parent decoration with the parent category.
+    /// Canceling this triple removes the relation.
+    ///
+    pub triple_id: String,
+
+    ///
+    /// The triple ID of the triple decorating the child category as having a parent.
+    ///
+    pub parent_decoration_id: String,
+
+    ///
+    /// The triple ID of the parent category.
+    ///
+    pub parent_id: String,
+
+    ///
+    /// The triple ID of the child category.
+    ///
+    pub child_id: String,
+}
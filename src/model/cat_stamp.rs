@@ -0,0 +1,19 @@
+///
+/// This struct tells who wrote something to a category space and when, like who tagged a point
+/// with a category or who deleted a category.
+/// Behind the scenes a stamp is a descriptor note, name being the actor and label the time, whose
+/// point is paired with a stamped decoration of the triple written.
+///
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CatStamp {
+
+    ///
+    /// The time of the write in milliseconds since the Unix epoch.
+    ///
+    pub time: u64,
+
+    ///
+    /// The ID of whoever made the write as set with CatService::set_actor. Empty if no actor was set.
+    ///
+    pub actor: String,
+}
@@ -0,0 +1,25 @@
+use crate::cat_error::CatError;
+use crate::model::cat_element::CatElement;
+
+///
+/// The outcome of tagging or untagging one point in a bulk operation like CatService::tag_many.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CatTagOutcome {
+
+    ///
+    /// The point was tagged or untagged, holding the CatElement added or removed.
+    ///
+    Written(CatElement),
+
+    ///
+    /// Nothing had to be written, because the point was already tagged, or not tagged when
+    /// untagging, or the pair repeats an earlier one.
+    ///
+    Skipped,
+
+    ///
+    /// The pair was rejected, holding why. The other pairs are not affected.
+    ///
+    Failed(CatError),
+}
@@ -0,0 +1,192 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::cat_error::CatError;
+
+///
+/// A typed value, like a property of a category.
+/// Values are ordered by type first, booleans before numbers before texts. Integers and decimals
+/// count as numbers and are compared by their numeric value.
+///
+#[derive(Clone, Debug)]
+pub enum CatValue {
+    Bool(bool),
+    Integer(i64),
+    Decimal(f64),
+    Text(String),
+}
+
+impl CatValue {
+
+    ///
+    /// The name of the type of the value as it is stored.
+    ///
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            CatValue::Bool(..) => "bool",
+            CatValue::Integer(..) => "integer",
+            CatValue::Decimal(..) => "decimal",
+            CatValue::Text(..) => "text",
+        }
+    }
+
+    ///
+    /// Reads a value back from the name of its type and its text as written by Display.
+    /// Fails with CatError::InvalidValue if the text does not fit the type.
+    ///
+    pub fn parse(type_name: &str, value: &str) -> Result<CatValue, CatError> {
+        let invalid = || CatError::InvalidValue(format!("\"{}\" is not a valid {}", value, type_name));
+        match type_name {
+            "bool" => value.parse().map(CatValue::Bool).map_err(|_|invalid()),
+            "integer" => value.parse().map(CatValue::Integer).map_err(|_|invalid()),
+            "decimal" => value.parse().map(CatValue::Decimal).map_err(|_|invalid()),
+            "text" => Ok(CatValue::Text(value.to_string())),
+            _ => Err(CatError::InvalidValue(format!("unknown type {}", type_name))),
+        }
+    }
+
+    fn type_rank(&self) -> u8 {
+        match self {
+            CatValue::Bool(..) => 0,
+            CatValue::Integer(..) | CatValue::Decimal(..) => 1,
+            CatValue::Text(..) => 2,
+        }
+    }
+}
+
+impl fmt::Display for CatValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CatValue::Bool(value) => write!(f, "{}", value),
+            CatValue::Integer(value) => write!(f, "{}", value),
+            CatValue::Decimal(value) => write!(f, "{}", value),
+            CatValue::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Ord for CatValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (CatValue::Bool(a), CatValue::Bool(b)) => a.cmp(b),
+            (CatValue::Integer(a), CatValue::Integer(b)) => a.cmp(b),
+            (CatValue::Integer(a), CatValue::Decimal(b)) => cmp_decimal(*b, *a).reverse(),
+            (CatValue::Decimal(a), CatValue::Integer(b)) => cmp_decimal(*a, *b),
+            (CatValue::Decimal(a), CatValue::Decimal(b)) => a.partial_cmp(b).unwrap_or_else(|| a.total_cmp(b)),
+            (CatValue::Text(a), CatValue::Text(b)) => a.cmp(b),
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
+    }
+}
+
+///
+/// Compares decimal with integer exactly. Casting integer to f64 would round it above 2^53 and
+/// make the order inconsistent, so a decimal within the range of i64 and without a fractional
+/// part is compared as an integer instead.
+/// NaN sorts like total_cmp does, a positive NaN above and a negative NaN below all numbers, while
+/// 0.0 and -0.0 are both equal to 0.
+///
+fn cmp_decimal(decimal: f64, integer: i64) -> Ordering {
+    if decimal.is_nan() {
+        return match decimal.is_sign_negative() {
+            true => Ordering::Less,
+            false => Ordering::Greater,
+        };
+    }
+    // -2^63 is exact as f64, 2^63 is the first f64 past i64::MAX
+    if decimal < i64::MIN as f64 {
+        return Ordering::Less;
+    }
+    if decimal >= -(i64::MIN as f64) {
+        return Ordering::Greater;
+    }
+    let whole = decimal.trunc();
+    match (whole as i64).cmp(&integer) {
+        Ordering::Equal if decimal > whole => Ordering::Greater,
+        Ordering::Equal if decimal < whole => Ordering::Less,
+        ordering => ordering,
+    }
+}
+
+impl PartialOrd for CatValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for CatValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CatValue {}
+
+impl From<bool> for CatValue {
+    fn from(value: bool) -> Self {
+        CatValue::Bool(value)
+    }
+}
+
+impl From<i64> for CatValue {
+    fn from(value: i64) -> Self {
+        CatValue::Integer(value)
+    }
+}
+
+impl From<f64> for CatValue {
+    fn from(value: f64) -> Self {
+        CatValue::Decimal(value)
+    }
+}
+
+impl From<&str> for CatValue {
+    fn from(value: &str) -> Self {
+        CatValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for CatValue {
+    fn from(value: String) -> Self {
+        CatValue::Text(value)
+    }
+}
+
+#[test]
+fn parse_test() {
+    for value in [CatValue::Bool(true), CatValue::Integer(-3), CatValue::Decimal(0.1), CatValue::Text("two words".to_string())] {
+        assert_eq!(CatValue::parse(value.type_name(), &value.to_string()).unwrap(), value);
+    }
+    assert!(CatValue::parse("integer", "1.5").is_err());
+    assert!(CatValue::parse("colour", "red").is_err());
+
+    assert!(CatValue::Integer(2) > CatValue::Decimal(1.5));
+    assert_eq!(CatValue::Integer(2), CatValue::Decimal(2.0));
+    assert!(CatValue::Bool(true) < CatValue::Integer(0));
+    assert!(CatValue::Integer(100) < CatValue::from("1"));
+    assert!(CatValue::Decimal(-0.5) < CatValue::Integer(0));
+    assert!(CatValue::Decimal(-1.5) > CatValue::Integer(-2));
+    assert_eq!(CatValue::Decimal(-0.0), CatValue::Integer(0));
+    assert_eq!(CatValue::Decimal(-0.0), CatValue::Decimal(0.0));
+}
+
+#[test]
+fn large_value_order_test() {
+    // 2^53 + 1 is the first integer an f64 cannot hold
+    let above = (1_i64 << 53) + 1;
+    let a = CatValue::Integer(above - 1);
+    let b = CatValue::Integer(above);
+    let c = CatValue::Decimal((1_i64 << 53) as f64);
+    assert_eq!(a, c);
+    assert!(c < b);
+    assert!(a < b);
+
+    assert!(CatValue::Integer(i64::MAX) < CatValue::Decimal(-(i64::MIN as f64)));
+    assert_eq!(CatValue::Integer(i64::MIN), CatValue::Decimal(i64::MIN as f64));
+    assert!(CatValue::Integer(i64::MIN) > CatValue::Decimal(f64::NEG_INFINITY));
+    assert!(CatValue::Integer(i64::MAX) < CatValue::Decimal(f64::NAN));
+
+    let mut values = vec![b.clone(), c.clone(), CatValue::Decimal(1e300), a.clone(), CatValue::Integer(i64::MAX)];
+    values.sort();
+    assert_eq!(values, vec![a, c, b, CatValue::Integer(i64::MAX), CatValue::Decimal(1e300)]);
+}
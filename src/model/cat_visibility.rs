@@ -0,0 +1,27 @@
+use crate::model::cat::Cat;
+use crate::model::cat_expr::CatExpr;
+
+///
+/// This struct tells whether a category is to be offered for a certain element and if not why.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatVisibility {
+
+    ///
+    /// The category including its condition if it has one.
+    ///
+    pub cat: Cat,
+
+    ///
+    /// The part of the category's condition the element does not satisfy, meaning the reason the
+    /// category is hidden. None if the category is visible.
+    ///
+    pub unmet: Option<CatExpr>,
+}
+
+impl CatVisibility {
+
+    pub fn is_visible(&self) -> bool {
+        self.unmet.is_none()
+    }
+}
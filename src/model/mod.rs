@@ -2,3 +2,13 @@ pub mod cat;
 pub mod cat_list;
 pub mod cat_element;
 pub mod cat_element_list;
+pub mod cat_event;
+pub mod cat_condition;
+pub mod cat_condition_issue;
+pub mod cat_expr;
+pub mod cat_facet;
+pub mod cat_parent;
+pub mod cat_stamp;
+pub mod cat_tag_outcome;
+pub mod cat_value;
+pub mod cat_visibility;
@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use infograph::{InfoTriple, Descriptor};
+
+use crate::cat_error::CatError;
+
+///
+/// The storage operations the category logic depends on.
+/// A category space is nothing but info triples and descriptor notes, so any storage that can read
+/// and append triples and read and write descriptor notes can back a CatService.
+/// Storage failures are reported as CatError::Storage.
+///
+pub trait CatStore {
+
+    ///
+    /// Returns all info triples of the info table graph_name in the order they were appended.
+    ///
+    fn get_all_info_triples_from_info_table(&mut self, graph_name: String) -> Result<Vec<InfoTriple>, CatError>;
+
+    ///
+    /// Appends a new info triple pairing id1 and id2 and returns it.
+    ///
+    fn create_infotriple(&mut self, id1: String, id2: String) -> Result<InfoTriple, CatError>;
+
+    ///
+    /// Returns the descriptor notes for the points in list keyed by point.
+    ///
+    fn get_descs_hashmap_for_list(&self, list: Vec<String>) -> Result<HashMap<String, Descriptor>, CatError>;
+
+    ///
+    /// Writes a new descriptor note for point and returns it with its desc_id set.
+    ///
+    fn create_desc(&mut self, point: String, name: String, label: String, description: String) -> Result<Descriptor, CatError>;
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use infograph::infotriple::Filesystem as tr_service_fs;
+use infograph::descnote::Filesystem as desc_service_fs;
+use infograph::framework::ig_desc::descriptor_tools;
+use infograph::{InfoTriple, Descriptor};
+
+use crate::cat_error::CatError;
+use crate::store::cat_store::CatStore;
+
+///
+/// CatStore keeping the categories of a space on the filesystem through the infograph services.
+/// The infograph filesystem services do not report I/O failures, so this storage never returns
+/// CatError::Storage.
+/// The services write new triples at the top of an info table file and read descriptor notes
+/// without their desc_id, both of which are made up for here to keep the CatStore contract.
+///
+#[derive(Clone)]
+pub struct CatStoreFS {
+    tr_service: tr_service_fs,
+    desc_service: desc_service_fs,
+}
+
+impl CatStoreFS {
+
+    pub fn new(space_id: String) -> Self {
+        CatStoreFS {
+            tr_service: tr_service_fs::new(space_id.clone()),
+            desc_service: desc_service_fs::new(space_id),
+        }
+    }
+}
+
+impl CatStore for CatStoreFS {
+
+    ///
+    /// The info table file holds the triple appended last first, so it is read backwards.
+    ///
+    fn get_all_info_triples_from_info_table(&mut self, graph_name: String) -> Result<Vec<InfoTriple>, CatError> {
+        let mut info_triples = self.tr_service.get_all_info_triples_from_info_table(graph_name);
+        info_triples.reverse();
+        Ok(info_triples)
+    }
+
+    fn create_infotriple(&mut self, id1: String, id2: String) -> Result<InfoTriple, CatError> {
+        Ok(self.tr_service.create_infotriple(id1, id2))
+    }
+
+    ///
+    /// The desc_id of a note found is derived from its content the way it is when the note is
+    /// written. A note without a name, label or description cannot be told from a missing one and
+    /// is returned without a desc_id, like a missing one.
+    ///
+    fn get_descs_hashmap_for_list(&self, list: Vec<String>) -> Result<HashMap<String, Descriptor>, CatError> {
+        let mut descs = self.desc_service.get_descs_hashmap_for_list(list);
+        descs
+            .values_mut()
+            .filter(|desc|!(desc.name.is_empty() && desc.label.is_empty() && desc.description.is_empty()))
+            .for_each(|desc|desc.desc_id = descriptor_tools::get_desc_id(desc));
+        Ok(descs)
+    }
+
+    fn create_desc(&mut self, point: String, name: String, label: String, description: String) -> Result<Descriptor, CatError> {
+        Ok(self.desc_service.create_desc(point, name, label, description))
+    }
+}
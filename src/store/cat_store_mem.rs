@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use infograph::framework::ig_desc::descriptor_tools;
+use infograph::framework::ig_tools::hashing_tools;
+use infograph::{InfoTriple, Descriptor};
+
+use crate::cat_error::CatError;
+use crate::store::cat_store::CatStore;
+
+///
+/// CatStore keeping the categories of a space in memory only.
+/// Triples and descriptor notes are created exactly like the filesystem storage does it, so ids are
+/// the same for the same input, but nothing survives the CatStoreMem instance.
+///
+#[derive(Clone, Debug, Default)]
+pub struct CatStoreMem {
+    tables: HashMap<String, Vec<InfoTriple>>,
+    descs: HashMap<String, Descriptor>,
+}
+
+impl CatStoreMem {
+
+    pub fn new() -> Self {
+        CatStoreMem {
+            tables: HashMap::new(),
+            descs: HashMap::new(),
+        }
+    }
+}
+
+impl CatStore for CatStoreMem {
+
+    fn get_all_info_triples_from_info_table(&mut self, graph_name: String) -> Result<Vec<InfoTriple>, CatError> {
+        Ok(self.tables
+            .get(&graph_name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    ///
+    /// Like the filesystem storage new triples always go to the main_table.
+    ///
+    fn create_infotriple(&mut self, id1: String, id2: String) -> Result<InfoTriple, CatError> {
+        let it = InfoTriple::from(hashing_tools::concat_n_hash(id1.as_str(), id2.as_str()));
+        self.tables
+            .entry("main_table".to_string())
+            .or_default()
+            .push(it.clone());
+        Ok(it)
+    }
+
+    ///
+    /// Points without a descriptor note get an empty one only holding the point, just like the
+    /// filesystem storage.
+    ///
+    fn get_descs_hashmap_for_list(&self, list: Vec<String>) -> Result<HashMap<String, Descriptor>, CatError> {
+        Ok(list
+            .into_iter()
+            .map(|point|{
+                let desc = self.descs
+                    .get(&point)
+                    .cloned()
+                    .unwrap_or(Descriptor { point: point.clone(), ..Descriptor::default() });
+                (point, desc)
+            })
+            .collect())
+    }
+
+    ///
+    /// If a point has more than one descriptor note the one with the lowest desc_id is the one
+    /// returned on reads, as that is the one found first in the sorted point index on the filesystem.
+    ///
+    fn create_desc(&mut self, point: String, name: String, label: String, description: String) -> Result<Descriptor, CatError> {
+        let mut desc = Descriptor {
+            point: point.trim().to_string(),
+            desc_id: "".to_string(),
+            name: name.trim().to_string(),
+            label: label.trim().to_string(),
+            description: description.trim().to_string(),
+        };
+        desc.set_desc_id(&descriptor_tools::get_desc_id(&desc));
+
+        let replace = match self.descs.get(&desc.point) {
+            Some(existing) => desc.desc_id < existing.desc_id,
+            None => true,
+        };
+        if replace {
+            self.descs.insert(desc.point.clone(), desc.clone());
+        }
+        Ok(desc)
+    }
+}
+
+#[test]
+fn create_infotriple_test() {
+    let mut store = CatStoreMem::new();
+    let it1 = store.create_infotriple("b".to_string(), "a".to_string()).unwrap();
+    let it2 = store.create_infotriple("a".to_string(), "b".to_string()).unwrap();
+    assert_eq!(it1.id, it2.id);
+    assert_eq!(it1.id1, "a");
+    assert_eq!(it1.id2, "b");
+
+    let table = store.get_all_info_triples_from_info_table("main_table".to_string()).unwrap();
+    assert_eq!(table.len(), 2);
+    assert!(store.get_all_info_triples_from_info_table("other_table".to_string()).unwrap().is_empty());
+}
+
+#[test]
+fn get_descs_hashmap_for_list_test() {
+    let mut store = CatStoreMem::new();
+    let desc = store.create_desc("a".to_string(), " name1 ".to_string(), "uri1".to_string(), "cat no. 1".to_string()).unwrap();
+    assert_eq!(desc.name, "name1");
+    assert!(!desc.desc_id.is_empty());
+
+    let descs = store.get_descs_hashmap_for_list(vec!["a".to_string(), "b".to_string()]).unwrap();
+    assert_eq!(descs.get("a").unwrap().desc_id, desc.desc_id);
+    assert_eq!(descs.get("b").unwrap().point, "b");
+    assert!(descs.get("b").unwrap().desc_id.is_empty());
+}
@@ -0,0 +1,3 @@
+pub mod cat_store;
+pub mod cat_store_fs;
+pub mod cat_store_mem;